@@ -1,21 +1,42 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 use bevy_ecs_ldtk::GridCoords;
 
 use crate::{
-    config::CHASE_SPEED,
-    grid::{Grid, Tile},
-    ldtk::{animation::new_animation, entities::player::PlayerTag},
-    pathfinding::Path,
+    grid::{Grid, GridLocation, Tile},
+    ldtk::{
+        animation::new_animation,
+        entities::player::{PlayerTag, PlayerVelocity},
+    },
+    pathfinding::{Path, PathRequest, PathUnreachable},
+    tuning::GameTuning,
 };
 
-use super::{MovementSpeed, INVESTIGATOR_ANIMATION_RUN};
+use super::{MovementSpeed, INVESTIGATOR_ANIMATION_RUN, TILE_SIZE};
 
 #[derive(Reflect, Clone, Component)]
-#[reflect(Component)]
+#[reflect(Component, MapEntities)]
 #[component(storage = "SparseSet")]
 pub struct Chase {
     pub target: Entity,
     pub player_last_seen: GridCoords,
+    /// Last known velocity of the player, in world units per seconds.
+    pub player_velocity: Vec2,
+    /// Where the chaser is heading, ahead of the player.
+    pub predicted: GridCoords,
+    /// Time since the last radio call, in seconds.
+    pub radio_elapsed: f32,
+}
+
+impl MapEntities for Chase {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.target = entity_mapper.map_entity(self.target);
+    }
 }
 
 #[derive(Reflect, Clone, Component)]
@@ -23,36 +44,94 @@ pub struct Chase {
 #[component(storage = "SparseSet")]
 pub struct Chased;
 
-pub fn chase_on_enter(mut commands: Commands, query: Query<Entity, Added<Chase>>) {
+pub fn chase_on_enter(
+    mut commands: Commands,
+    query: Query<Entity, Added<Chase>>,
+    tuning: Res<GameTuning>,
+) {
     for entity in &query {
-        commands.entity(entity).insert(MovementSpeed(CHASE_SPEED));
+        commands
+            .entity(entity)
+            .insert(MovementSpeed(tuning.chase_speed));
         commands
             .entity(entity)
             .insert(new_animation(INVESTIGATOR_ANIMATION_RUN));
     }
 }
 
-/// While [`Chase`], update [`Path`] to reflect target new position
+/// Where the player will be after moving at `velocity` for `time`, stopping at the first wall on the way.
+pub fn predict_location(
+    grid: &Grid<Tile>,
+    from: &GridCoords,
+    velocity: Vec2,
+    time: f32,
+) -> GridCoords {
+    // In grid units.
+    let offset = velocity * time / TILE_SIZE.x as f32;
+    let steps = offset.length().ceil() as i32;
+    let start = Vec2::new(from.x as f32, from.y as f32);
+
+    let mut predicted = *from;
+    for step in 1..=steps {
+        let position = (start + offset * step as f32 / steps as f32).round();
+        let location = GridLocation::new(position.x as i32, position.y as i32);
+
+        if !Grid::<Tile>::valid_index(&location) || grid.occupied(&location) {
+            break;
+        }
+        predicted = location.into();
+    }
+
+    predicted
+}
+
+/// While [`Chase`], request a [`Path`] to the target new position.
+/// Aims ahead of the player, unless close enough to catch them or the tile ahead can't be reached.
 pub fn chase_update(
     mut commands: Commands,
-    player: Query<&GridCoords, With<PlayerTag>>,
-    mut query: Query<(Entity, &GridCoords, &mut Chase)>,
+    player: Query<(&GridCoords, &PlayerVelocity), With<PlayerTag>>,
+    mut query: Query<(
+        Entity,
+        &GridCoords,
+        &mut Chase,
+        Option<Ref<PathUnreachable>>,
+    )>,
     grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
 ) {
-    for (entity, entity_coords, mut chase) in &mut query {
-        let Ok(target_coords) = player.get_single() else {
+    for (entity, entity_coords, mut chase, unreachable) in &mut query {
+        let Ok((target_coords, velocity)) = player.get_single() else {
             continue;
         };
 
-        // Calculate path to target
-        let path = grid.path_to(&entity_coords, &target_coords);
+        let distance =
+            GridLocation::from(*entity_coords).distance(&GridLocation::from(*target_coords));
+        let predicted = if distance <= tuning.chase_lead_min_distance as usize {
+            *target_coords
+        } else {
+            predict_location(&grid, target_coords, velocity.0, tuning.chase_lead_time)
+        };
 
-        // if found a valid path to target
-        if let Ok(path) = path {
-            chase.player_last_seen = *target_coords;
-            commands.entity(entity).insert(path);
+        // Go for the player instead of a tile ahead that can't be reached.
+        let predicted = if PathUnreachable::is_goal(unreachable.as_deref(), &predicted) {
+            *target_coords
         } else {
-            warn!("Could not find a Path from {} to {}", entity, chase.target);
+            predicted
+        };
+
+        chase.player_last_seen = *target_coords;
+        chase.player_velocity = velocity.0;
+        chase.predicted = predicted;
+
+        // Only replanned when the goal changes tile, see `PathPlanner`.
+        commands
+            .entity(entity)
+            .insert(PathRequest { goal: predicted });
+
+        if let Some(unreachable) = unreachable {
+            if unreachable.is_changed() && unreachable.goal == *target_coords {
+                warn!("Could not find a Path from {} to {}", entity, chase.target);
+            }
         }
     }
 }
@@ -61,6 +140,9 @@ pub fn chase_update(
 pub fn chase_on_exit(mut commands: Commands, mut query: RemovedComponents<Chase>) {
     for entity in query.read() {
         commands.entity(entity).remove::<Path>();
+        commands
+            .entity(entity)
+            .remove::<(PathRequest, PathUnreachable)>();
         commands.entity(entity).remove::<MovementSpeed>();
     }
 }
@@ -3,43 +3,44 @@ use bevy_rapier2d::prelude::{Collider, RigidBody};
 
 use crate::{
     game_mode::Score,
-    ldtk::{animation::new_animation, entities::EnemyTag},
+    ldtk::{
+        animation::new_animation,
+        entities::{add_corpse_sensor, CorpseHidden},
+    },
 };
 
-use super::{Idle, RunAway, TalkToInvestigator, Wander, VILLAGER_ANIMATION_DEATH};
+use super::VILLAGER_ANIMATION_DEATH;
 
+/// Only villagers can die, see [`VILLAGER_STATE_MACHINE`](super::state_machine::VILLAGER_STATE_MACHINE).
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
 #[component(storage = "SparseSet")]
 pub struct Dead;
 
+/// Sets up the corpse and counts the kill, this also runs when a level is resumed.
+/// The kill noise is sent by the kill action itself, in the player controller.
 pub fn dead_on_enter(
     mut commands: Commands,
-    query: Query<(Entity, &EnemyTag), Added<Dead>>,
+    query: Query<(Entity, Has<CorpseHidden>), Added<Dead>>,
     mut score: ResMut<Score>,
 ) {
-    for (entity, tag) in &query {
-        // Only villagers should have the Dead state.
-        if *tag != EnemyTag::Villager {
-            commands.entity(entity).remove::<Dead>();
-            continue;
-        }
-
+    for (entity, hidden) in &query {
         score.villager_killed();
 
-        // Remove any states the villager might be in.
+        // Corpses don't block the way.
         commands
             .entity(entity)
-            .remove::<Idle>()
-            .remove::<Wander>()
-            .remove::<RunAway>()
-            .remove::<TalkToInvestigator>()
             .remove::<Collider>()
             .remove::<RigidBody>();
 
         // Remove any interaction prompt or interaction sensors.
         commands.entity(entity).despawn_descendants();
 
+        // The corpse can be dragged, unless already stashed (when resuming a level).
+        if !hidden {
+            add_corpse_sensor(&mut commands, entity);
+        }
+
         // Play death animation
         commands
             .entity(entity)
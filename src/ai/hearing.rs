@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::GridCoords;
+
+use crate::{
+    config::{FOOTSTEP_INTERVAL, SCREAM_INTERVAL},
+    grid::{Grid, GridLocation, Tile},
+    ldtk::{
+        animation::new_animation,
+        entities::{player::PlayerTag, EnemyTag},
+    },
+    pathfinding::Path,
+    player_controller::PlayerIsHidding,
+    player_input::PlayerInput,
+    tuning::GameTuning,
+};
+
+use super::{
+    state_machine::TransitionExt, Idle, Investigate, Patrol, RunAway, Wander,
+    INVESTIGATOR_ANIMATION_RUN,
+};
+
+/// Something investigators can hear.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Noise {
+    /// Where the noise comes from, in world units.
+    pub location: Vec2,
+    /// Where to go investigate.
+    pub target: GridCoords,
+    /// Fraction of the hearing range.
+    pub loudness: f32,
+    /// Not heard behind more than `max_occluding_walls`.
+    /// Noise makers are built to be heard, so they aren't.
+    pub occluded: bool,
+}
+
+/// The player makes noise when moving, louder when moving faster.
+pub fn player_footsteps(
+    player: Query<(&Transform, &GridCoords), (With<PlayerTag>, Without<PlayerIsHidding>)>,
+    player_input: Res<PlayerInput>,
+    mut noises: EventWriter<Noise>,
+    mut elapsed: Local<f32>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    let Ok((transform, coords)) = player.get_single() else {
+        return;
+    };
+
+    let speed = player_input.movement.length();
+    if speed < 0.1 {
+        *elapsed = 0.;
+        return;
+    }
+
+    *elapsed += time.delta_seconds();
+    if *elapsed >= FOOTSTEP_INTERVAL {
+        *elapsed -= FOOTSTEP_INTERVAL;
+
+        noises.send(Noise {
+            location: transform.translation.xy(),
+            target: *coords,
+            loudness: tuning.footstep_loudness * speed,
+            occluded: true,
+        });
+    }
+}
+
+/// Villagers in [`RunAway`] scream every now and then.
+pub fn villagers_scream(
+    query: Query<(&Transform, &GridCoords, &EnemyTag), With<RunAway>>,
+    mut noises: EventWriter<Noise>,
+    mut elapsed: Local<f32>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    *elapsed += time.delta_seconds();
+    if *elapsed < SCREAM_INTERVAL {
+        return;
+    }
+    *elapsed -= SCREAM_INTERVAL;
+
+    for (transform, coords, tag) in &query {
+        if *tag == EnemyTag::Villager {
+            noises.send(Noise {
+                location: transform.translation.xy(),
+                target: *coords,
+                loudness: tuning.scream_loudness,
+                occluded: true,
+            });
+        }
+    }
+}
+
+/// If an investigator in [`Idle`], [`Wander`], [`Patrol`] or [`Investigate`] hears a noise within range and not behind too many walls (unless it carries through), go [`Investigate`] it.
+/// An investigator already in [`Investigate`] ignores noises in the area it searches, and otherwise moves its target without starting over.
+pub fn hearing_to_investigating(
+    mut commands: Commands,
+    mut noises: EventReader<Noise>,
+    mut query: Query<
+        (Entity, &Transform, &EnemyTag, Option<&mut Investigate>),
+        Or<(With<Idle>, With<Wander>, With<Patrol>, With<Investigate>)>,
+    >,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    let noises: Vec<Noise> = noises.read().copied().collect();
+    if noises.is_empty() {
+        return;
+    }
+
+    for (entity, transform, tag, investigate) in &mut query {
+        if *tag != EnemyTag::Investigator {
+            continue;
+        }
+
+        let location = transform.translation.xy();
+
+        // Go to the closest noise heard.
+        let heard = noises
+            .iter()
+            .filter(|noise| {
+                location.distance(noise.location)
+                    <= tuning.investigator_hearing_range * noise.loudness
+                    && (!noise.occluded
+                        || grid.walls_between(location, noise.location)
+                            <= tuning.max_occluding_walls)
+            })
+            .min_by(|a, b| {
+                location
+                    .distance(a.location)
+                    .total_cmp(&location.distance(b.location))
+            });
+
+        let Some(noise) = heard else {
+            continue;
+        };
+
+        let Some(mut investigate) = investigate else {
+            commands.entity(entity).transition_to(Investigate {
+                target: noise.target,
+                ..Default::default()
+            });
+            continue;
+        };
+
+        let target = GridLocation::from(investigate.target);
+        if target.distance(&GridLocation::from(noise.target))
+            <= tuning.investigating_radius as usize
+        {
+            continue;
+        }
+
+        investigate.target = noise.target;
+        investigate.reached_area = false;
+        investigate.searching = None;
+        commands.entity(entity).remove::<Path>();
+        if investigate.inspecting.take().is_some() {
+            commands
+                .entity(entity)
+                .insert(new_animation(INVESTIGATOR_ANIMATION_RUN));
+        }
+    }
+}
@@ -1,24 +1,14 @@
-use std::time::Instant;
-
-use bevy::prelude::*;
+use bevy::{prelude::*, time::Stopwatch};
 
 use crate::ldtk::{animation::new_animation, entities::EnemyTag};
 
 use super::{INVESTIGATOR_ANIMATION_IDLE, VILLAGER_ANIMATION_IDLE};
 
-#[derive(Reflect, Clone, Component)]
+#[derive(Reflect, Clone, Component, Default)]
 #[reflect(Component)]
 #[component(storage = "SparseSet")]
 pub struct Idle {
-    pub start: Instant,
-}
-
-impl Default for Idle {
-    fn default() -> Self {
-        Idle {
-            start: Instant::now(),
-        }
-    }
+    pub elapsed: Stopwatch,
 }
 
 pub fn idle_on_enter(mut commands: Commands, query: Query<(Entity, &EnemyTag), Added<Idle>>) {
@@ -1,40 +1,64 @@
-use std::time::Instant;
-
-use bevy::prelude::*;
-use bevy_ecs_ldtk::GridCoords;
+use bevy::{ecs::system::SystemParam, prelude::*, time::Stopwatch};
+use bevy_ecs_ldtk::{
+    utils::{grid_coords_to_translation, translation_to_grid_coords},
+    GridCoords,
+};
 use bevy_rand::prelude::{GlobalEntropy, WyRand};
+use rand_core::RngCore;
 
 use crate::{
-    config::{INVESTIGATING_RADIUS, RUNNING_SPEED},
     grid::{Grid, Tile},
-    ldtk::animation::new_animation,
+    ldtk::{
+        animation::new_animation,
+        entities::{hidding_spot::HiddingSpotExit, player::PlayerTag, Aim},
+    },
     pathfinding::Path,
+    player_controller::{leave_hidding_spot, PlayerIsHidding, SeenHidding},
+    tuning::GameTuning,
+    utils::remap_rand_f32,
 };
 
-use super::{MovementSpeed, INVESTIGATOR_ANIMATION_RUN};
+use super::{
+    search::{next_search_tile, SearchContext, SearchMap},
+    state_machine::TransitionExt,
+    Chase, Chased, MovementSpeed, Suspicion, INVESTIGATOR_ANIMATION_INSPECT,
+    INVESTIGATOR_ANIMATION_RUN, TILE_SIZE,
+};
 
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
 #[component(storage = "SparseSet")]
 pub struct Investigate {
     pub target: GridCoords,
-    pub start: Instant,
+    pub elapsed: Stopwatch,
     pub reached_area: bool,
+    /// Tile being searched, once the area is reached.
+    pub searching: Option<GridCoords>,
+    /// Time spent inspecting the hidding spot at the searched tile.
+    pub inspecting: Option<Stopwatch>,
 }
 
 impl Default for Investigate {
     fn default() -> Self {
         Investigate {
             target: GridCoords::default(),
-            start: Instant::now(),
+            elapsed: Stopwatch::new(),
             reached_area: false,
+            searching: None,
+            inspecting: None,
         }
     }
 }
 
-pub fn investigate_on_enter(mut commands: Commands, query: Query<Entity, Added<Investigate>>) {
+pub fn investigate_on_enter(
+    mut commands: Commands,
+    query: Query<Entity, Added<Investigate>>,
+    tuning: Res<GameTuning>,
+) {
     for entity in &query {
-        commands.entity(entity).insert(MovementSpeed(RUNNING_SPEED));
+        commands
+            .entity(entity)
+            .insert(MovementSpeed(tuning.running_speed));
 
         commands
             .entity(entity)
@@ -42,28 +66,169 @@ pub fn investigate_on_enter(mut commands: Commands, query: Query<Entity, Added<I
     }
 }
 
-/// When entity doens't have a [`Path`], pick a location around the [`Investigate`].target, within [`Investigate`].range
+/// Queries of [`investigate_update`].
+#[derive(SystemParam)]
+pub struct InvestigateQueries<'w, 's> {
+    /// Investigators waiting for their next step.
+    investigate: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GridCoords,
+            &'static mut Investigate,
+            &'static mut Aim,
+            &'static Suspicion,
+        ),
+        Without<Path>,
+    >,
+    /// Investigators on their way.
+    searchers: Query<'w, 's, (Entity, &'static Investigate), With<Path>>,
+    player: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut Transform,
+            &'static PlayerIsHidding,
+            Has<SeenHidding>,
+        ),
+        With<PlayerTag>,
+    >,
+    hidding_spots:
+        Query<'w, 's, (&'static Transform, &'static HiddingSpotExit), Without<PlayerTag>>,
+}
+
+/// When entity doesn't have a [`Path`], go to the [`Investigate`].target, then search the area around it.
+/// Searched hidding spots may be inspected, pulling the player out if they were seen getting in or if [`Suspicion`] is high enough.
 pub fn investigate_update(
     mut commands: Commands,
-    mut investigate: Query<(Entity, &GridCoords, &mut Investigate), Without<Path>>,
+    queries: InvestigateQueries,
+    mut search_map: ResMut<SearchMap>,
     grid: Res<Grid<Tile>>,
     mut rng: ResMut<GlobalEntropy<WyRand>>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
 ) {
-    for (entity, coords, mut investigate) in &mut investigate {
+    let InvestigateQueries {
+        mut investigate,
+        searchers,
+        mut player,
+        hidding_spots,
+    } = queries;
+
+    let now = time.elapsed_seconds();
+    // Hidding spots are searched from their exit.
+    let (hidding_spots, hidding_spot_locations): (Vec<GridCoords>, Vec<Vec2>) = hidding_spots
+        .iter()
+        .map(|(transform, exit)| {
+            (
+                translation_to_grid_coords(exit.0, TILE_SIZE),
+                transform.translation.xy(),
+            )
+        })
+        .unzip();
+
+    // Tiles other investigators are on their way to search.
+    let mut claimed: Vec<(Entity, GridCoords)> = searchers
+        .iter()
+        .chain(
+            investigate
+                .iter()
+                .map(|(entity, _, investigate, _, _)| (entity, investigate)),
+        )
+        .filter_map(|(entity, investigate)| Some((entity, investigate.searching?)))
+        .collect();
+
+    for (entity, coords, mut investigate, mut aim, suspicion) in &mut investigate {
         if *coords == investigate.target {
             investigate.reached_area = true;
         }
 
-        if investigate.reached_area {
-            if let Ok(new_target) =
-                grid.find_nearby(&investigate.target, INVESTIGATING_RADIUS, rng.as_mut())
-            {
-                if let Ok(path) = grid.path_to(&coords, &new_target) {
-                    commands.entity(entity).insert(path);
+        if !investigate.reached_area {
+            if let Ok(path) = grid.path_to(coords, &investigate.target) {
+                commands.entity(entity).insert(path);
+            }
+            continue;
+        }
+
+        if let Some(inspecting) = investigate.inspecting.as_mut() {
+            inspecting.tick(time.delta());
+            if inspecting.elapsed_secs() < tuning.inspect_time {
+                continue;
+            }
+
+            investigate.inspecting = None;
+            commands
+                .entity(entity)
+                .insert(new_animation(INVESTIGATOR_ANIMATION_RUN));
+
+            // Found the player, if hidding there and the investigator knows it.
+            if let Ok((player, mut player_transform, hidding, seen)) = player.get_single_mut() {
+                if translation_to_grid_coords(hidding.0, TILE_SIZE) == *coords
+                    && (seen || suspicion.0 >= tuning.inspect_catch_suspicion)
+                {
+                    leave_hidding_spot(&mut commands, player, &mut player_transform, hidding);
+
+                    commands.entity(entity).transition_to(Chase {
+                        target: player,
+                        player_last_seen: *coords,
+                        player_velocity: Vec2::ZERO,
+                        predicted: *coords,
+                        radio_elapsed: 0.,
+                    });
+                    commands.entity(player).insert(Chased);
+                    continue;
                 }
             }
-        } else {
-            if let Ok(path) = grid.path_to(&coords, &investigate.target) {
+        } else if investigate.searching == Some(*coords) {
+            // Arrived at the searched tile.
+            search_map.mark(*coords, now);
+
+            if let Some(index) = hidding_spots.iter().position(|spot| spot == coords) {
+                if remap_rand_f32(rng.next_u32(), 0., 1.) < tuning.inspect_chance {
+                    investigate.inspecting = Some(Stopwatch::new());
+                    aim.0 = (hidding_spot_locations[index]
+                        - grid_coords_to_translation(*coords, TILE_SIZE))
+                    .try_normalize()
+                    .unwrap_or(aim.0);
+                    commands
+                        .entity(entity)
+                        .insert(new_animation(INVESTIGATOR_ANIMATION_INSPECT));
+                    continue;
+                }
+            }
+        }
+
+        claimed.retain(|(searcher, _)| *searcher != entity);
+        let others: Vec<GridCoords> = claimed.iter().map(|(_, claim)| *claim).collect();
+
+        // Nothing left to search, look around randomly.
+        let next = next_search_tile(
+            &grid,
+            &investigate.target,
+            coords,
+            &SearchContext {
+                search_map: &search_map,
+                claimed: &others,
+                hidding_spots: &hidding_spots,
+                now,
+                tuning: &tuning,
+            },
+        )
+        .or_else(|| {
+            grid.find_nearby(
+                &investigate.target,
+                tuning.investigating_radius,
+                rng.as_mut(),
+            )
+            .ok()
+        });
+
+        if let Some(next) = next {
+            if let Ok(path) = grid.path_to(coords, &next) {
+                investigate.searching = Some(next);
+                claimed.push((entity, next));
                 commands.entity(entity).insert(path);
             }
         }
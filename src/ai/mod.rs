@@ -6,29 +6,57 @@ use bevy_ecs_ldtk::utils::grid_coords_to_translation;
 
 mod chase;
 mod dead;
+mod hearing;
 mod idle;
 mod investigate;
+mod panic;
+mod patrol;
+mod radio;
 mod run_away;
+mod schedule;
+mod search;
+mod state_machine;
+mod suspicion;
 mod talk_to_investigator;
 mod transitions;
+mod visit;
 mod wander;
 
-pub use chase::Chased;
 use chase::*;
+pub use chase::{Chase, Chased};
 pub use dead::Dead;
 use dead::*;
+pub use hearing::Noise;
+use hearing::*;
+pub use idle::Idle;
 use idle::*;
+pub use investigate::Investigate;
 use investigate::*;
+pub use panic::CorpseDiscovered;
+use panic::*;
+pub use patrol::Patrol;
+use patrol::*;
+use radio::*;
+pub use run_away::RunAway;
 use run_away::*;
+pub use schedule::{ScheduledActivity, VillagerSchedule};
+use search::*;
+pub use state_machine::TransitionExt;
+pub use suspicion::{Suspicion, SuspicionIndicator};
 use talk_to_investigator::*;
+pub use talk_to_investigator::{HasTakedToInvestigator, TalkToInvestigator};
 use transitions::*;
+pub use visit::Visit;
+use visit::*;
+pub use wander::Wander;
 use wander::*;
 
 use crate::{
     config::*,
     ldtk::{animation::AnimationOffset, entities::Aim, Light},
     pathfinding::Path,
-    states::PlayingState,
+    seed::SeededRng,
+    states::{GameState, PlayingState},
 };
 
 // All the logic for transitioning from different Tasks will be executed during this schedule.
@@ -52,6 +80,8 @@ pub struct AiPlugin;
 
 impl Plugin for AiPlugin {
     fn build(&self, app: &mut App) {
+        state_machine::register_state_hooks(app.world_mut());
+
         app.init_schedule(AiTransition);
         app.init_schedule(AiOnEnter);
         app.init_schedule(AiOnExit);
@@ -69,13 +99,18 @@ impl Plugin for AiPlugin {
             AiTransition,
             (
                 notice_player,
+                notice_player_hidding,
                 idle_to_wandering,
                 wandering_to_idle,
-                idle_or_wandering_to_investigating,
+                hearing_to_investigating,
+                radio_to_investigating,
+                witnessing_to_running_away,
+                corpse_alarm,
                 chasing_to_investigating,
                 chasing_to_killing,
                 investigating_to_idle,
                 run_away_to_idle,
+                visiting_to_idle,
                 running_away_to_talk_to_investigator,
                 talk_to_investigator_to_running_away,
             )
@@ -88,6 +123,8 @@ impl Plugin for AiPlugin {
                 investigate_on_exit,
                 run_away_on_exit,
                 wander_on_exit,
+                patrol_on_exit,
+                visit_on_exit,
                 talk_to_investigator_on_exit,
             )
                 .run_if(in_state(PlayingState::Playing)),
@@ -98,8 +135,10 @@ impl Plugin for AiPlugin {
                 idle_on_enter,
                 chase_on_enter,
                 investigate_on_enter,
-                run_away_on_enter,
-                wander_on_enter,
+                // Both draw from the seeded rng.
+                (run_away_on_enter, wander_on_enter).chain(),
+                patrol_on_enter,
+                visit_on_enter,
                 talk_to_investigator_on_enter,
                 dead_on_enter,
             )
@@ -109,18 +148,47 @@ impl Plugin for AiPlugin {
             Update,
             (
                 chase_update,
-                investigate_update,
+                investigate_update.in_set(SeededRng::Ai),
+                mark_searched,
+                patrol_update,
+                visit_update,
                 talk_to_investigator_update,
                 follow_path,
                 update_animation_aim,
+                player_footsteps,
+                villagers_scream,
+                radio_contact,
+                suspicion::update_indicators,
             )
                 .run_if(in_state(PlayingState::Playing)),
         )
+        .add_systems(
+            Update,
+            (suspicion::enemy_added, schedule::villager_schedule_added),
+        )
         .add_systems(
             PostUpdate,
             (check_empty_path, nothing_to_idle).run_if(in_state(PlayingState::Playing)),
         )
-        .register_type::<Chase>();
+        .add_systems(OnEnter(GameState::Reset), reset_search_map)
+        .init_resource::<SearchMap>()
+        .add_event::<Noise>()
+        .add_event::<RadioCall>()
+        .register_type::<Idle>()
+        .register_type::<Wander>()
+        .register_type::<Patrol>()
+        .register_type::<Visit>()
+        .register_type::<VillagerSchedule>()
+        .register_type::<Investigate>()
+        .register_type::<Chase>()
+        .register_type::<Chased>()
+        .register_type::<RunAway>()
+        .register_type::<TalkToInvestigator>()
+        .register_type::<HasTakedToInvestigator>()
+        .register_type::<Dead>()
+        .register_type::<CorpseDiscovered>()
+        .register_type::<Suspicion>()
+        .register_type::<SuspicionIndicator>();
     }
 }
 
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::GridCoords;
+
+use crate::{
+    grid::{Grid, Tile},
+    ldtk::entities::{Aim, CorpseHidden, EnemyTag},
+    light_map::LightMap,
+    player_controller::is_location_visible,
+    tuning::GameTuning,
+};
+
+use super::{state_machine::TransitionExt, Dead, Idle, Noise, RunAway, Visit, Wander};
+
+/// Dead villager already found by someone, so it doesn't cause panic again.
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+pub struct CorpseDiscovered;
+
+/// Calm villagers seeing a villager [`RunAway`] panic and run away from the same threat.
+/// Seeing a corpse for the first time makes them run away from it, and report it to an investigator on the way.
+pub fn witnessing_to_running_away(
+    mut commands: Commands,
+    villagers: Query<
+        (Entity, &Transform, &Aim, &EnemyTag),
+        Or<(With<Idle>, With<Wander>, With<Visit>)>,
+    >,
+    fleeing: Query<(&Transform, &RunAway)>,
+    corpses: Query<
+        (Entity, &Transform, &GridCoords),
+        (With<Dead>, Without<CorpseDiscovered>, Without<CorpseHidden>),
+    >,
+    grid: Res<Grid<Tile>>,
+    light_map: Res<LightMap>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, transform, aim, tag) in &villagers {
+        if *tag != EnemyTag::Villager {
+            continue;
+        }
+
+        let location = transform.translation.xy();
+        let can_see = |other: Vec2| {
+            is_location_visible(
+                other,
+                location,
+                *aim,
+                tuning.villagers_view_range,
+                tuning.villagers_view_half_angle,
+                &light_map,
+                &grid,
+            )
+        };
+
+        let corpse = corpses
+            .iter()
+            .find(|(_, corpse_transform, _)| can_see(corpse_transform.translation.xy()));
+
+        let threat = if let Some((corpse, _, corpse_coords)) = corpse {
+            commands.entity(corpse).insert(CorpseDiscovered);
+            *corpse_coords
+        } else if let Some((_, run_away)) = fleeing
+            .iter()
+            .find(|(fleeing_transform, _)| can_see(fleeing_transform.translation.xy()))
+        {
+            run_away.player_last_seen
+        } else {
+            continue;
+        };
+
+        commands.entity(entity).transition_to(RunAway {
+            player_last_seen: threat,
+        });
+    }
+}
+
+/// Any enemy seeing a corpse that isn't hidden raises the alarm, investigators nearby go [`Investigate`](super::Investigate) the body.
+pub fn corpse_alarm(
+    mut commands: Commands,
+    enemies: Query<(&Transform, &Aim, &EnemyTag), Without<Dead>>,
+    corpses: Query<
+        (Entity, &Transform, &GridCoords),
+        (With<Dead>, Without<CorpseDiscovered>, Without<CorpseHidden>),
+    >,
+    mut noises: EventWriter<Noise>,
+    grid: Res<Grid<Tile>>,
+    light_map: Res<LightMap>,
+    tuning: Res<GameTuning>,
+) {
+    for (corpse, corpse_transform, corpse_coords) in &corpses {
+        let corpse_location = corpse_transform.translation.xy();
+
+        let witness = enemies.iter().find(|(transform, aim, tag)| {
+            let (view_range, view_half_angle) = match tag {
+                EnemyTag::Investigator => (
+                    tuning.investigator_view_range,
+                    tuning.investigator_view_half_angle,
+                ),
+                EnemyTag::Villager => (
+                    tuning.villagers_view_range,
+                    tuning.villagers_view_half_angle,
+                ),
+            };
+
+            is_location_visible(
+                corpse_location,
+                transform.translation.xy(),
+                **aim,
+                view_range,
+                view_half_angle,
+                &light_map,
+                &grid,
+            )
+        });
+
+        if let Some((witness_transform, _, _)) = witness {
+            commands.entity(corpse).insert(CorpseDiscovered);
+
+            // The witness shouts, investigators hearing it come to the body.
+            noises.send(Noise {
+                location: witness_transform.translation.xy(),
+                target: *corpse_coords,
+                loudness: tuning.corpse_alarm_loudness,
+                occluded: true,
+            });
+        }
+    }
+}
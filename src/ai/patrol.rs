@@ -0,0 +1,151 @@
+use bevy::{prelude::*, time::Stopwatch};
+use bevy_ecs_ldtk::GridCoords;
+
+use crate::{
+    grid::{Grid, Tile},
+    ldtk::{
+        animation::new_animation,
+        entities::{Aim, PatrolRoute},
+    },
+    pathfinding::Path,
+    tuning::GameTuning,
+};
+
+use super::{MovementSpeed, INVESTIGATOR_ANIMATION_IDLE, INVESTIGATOR_ANIMATION_WALK};
+
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Patrol {
+    /// Waypoint of the [`PatrolRoute`] currently walked to.
+    pub index: usize,
+    /// Time spent at the waypoint, once reached.
+    pub waiting: Option<Stopwatch>,
+}
+
+impl Patrol {
+    /// Resume the route from the closest waypoint.
+    pub fn closest(route: &PatrolRoute, coords: &GridCoords) -> Self {
+        let index = route
+            .0
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, waypoint)| {
+                (waypoint.coords.x - coords.x).abs() + (waypoint.coords.y - coords.y).abs()
+            })
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+
+        Patrol {
+            index,
+            waiting: None,
+        }
+    }
+}
+
+/// Walk to the current waypoint, skipping to the next ones while they can't be reached.
+fn walk_to_waypoint(
+    commands: &mut Commands,
+    entity: Entity,
+    coords: &GridCoords,
+    patrol: &mut Patrol,
+    route: &PatrolRoute,
+    grid: &Grid<Tile>,
+    tuning: &GameTuning,
+) {
+    for _ in 0..route.0.len() {
+        let Some(waypoint) = route.0.get(patrol.index) else {
+            return;
+        };
+
+        match grid.path_to(coords, &waypoint.coords) {
+            Ok(path) => {
+                commands.entity(entity).insert(path);
+                commands
+                    .entity(entity)
+                    .insert(MovementSpeed(tuning.normal_speed))
+                    .insert(new_animation(INVESTIGATOR_ANIMATION_WALK));
+                return;
+            }
+            Err(_) => {
+                warn!(
+                    "{:?} can't reach waypoint {} of its patrol route, skipping it",
+                    entity, patrol.index
+                );
+                patrol.index = (patrol.index + 1) % route.0.len();
+            }
+        }
+    }
+}
+
+/// When [`Patrol`] is added, generate a [`Path`] to the current waypoint.
+pub fn patrol_on_enter(
+    mut commands: Commands,
+    mut query: Query<(Entity, &GridCoords, &mut Patrol, &PatrolRoute), Added<Patrol>>,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, coords, mut patrol, route) in &mut query {
+        walk_to_waypoint(
+            &mut commands,
+            entity,
+            coords,
+            &mut patrol,
+            route,
+            &grid,
+            &tuning,
+        );
+    }
+}
+
+/// Once a waypoint is reached, face the authored direction, wait, then walk to the next one.
+pub fn patrol_update(
+    mut commands: Commands,
+    mut query: Query<(Entity, &GridCoords, &mut Patrol, &PatrolRoute, &mut Aim), Without<Path>>,
+    grid: Res<Grid<Tile>>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, coords, mut patrol, route, mut aim) in &mut query {
+        let Some(waypoint) = route.0.get(patrol.index) else {
+            continue;
+        };
+
+        let Some(waiting) = patrol.waiting.as_mut() else {
+            if let Some(facing) = waypoint.facing {
+                aim.0 = facing;
+            }
+            commands
+                .entity(entity)
+                .insert(new_animation(INVESTIGATOR_ANIMATION_IDLE));
+
+            patrol.waiting = Some(Stopwatch::new());
+            continue;
+        };
+
+        if waiting.tick(time.delta()).elapsed_secs() < waypoint.wait {
+            continue;
+        }
+
+        patrol.index = (patrol.index + 1) % route.0.len();
+        patrol.waiting = None;
+
+        walk_to_waypoint(
+            &mut commands,
+            entity,
+            coords,
+            &mut patrol,
+            route,
+            &grid,
+            &tuning,
+        );
+    }
+}
+
+/// When [`Patrol`] is removed, remove any [`Path`] and [`MovementSpeed`].
+pub fn patrol_on_exit(mut commands: Commands, mut query: RemovedComponents<Patrol>) {
+    for entity in query.read() {
+        commands.entity(entity).remove::<Path>();
+        commands.entity(entity).remove::<MovementSpeed>();
+    }
+}
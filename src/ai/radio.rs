@@ -0,0 +1,127 @@
+// Radio
+// Investigators call the rest of the squad while chasing the player.
+// Those in range run ahead of the player to cut off the way, and search together once contact is lost.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::GridCoords;
+
+use crate::{
+    grid::{Grid, GridLocation, Tile},
+    ldtk::entities::{
+        player::{PlayerTag, PlayerVelocity},
+        EnemyTag,
+    },
+    tuning::GameTuning,
+};
+
+use super::{state_machine::TransitionExt, Chase, Idle, Investigate, Patrol, Wander};
+
+#[derive(Clone, Copy, Debug)]
+pub enum RadioMessage {
+    /// Chasing the player, heading that way.
+    Contact { player: GridCoords, heading: Vec2 },
+    /// Lost the player, last seen there.
+    ContactLost { last_seen: GridCoords },
+}
+
+/// Call from an investigator to the rest of the squad.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RadioCall {
+    pub caller: Entity,
+    /// Where the caller is, in world units.
+    pub location: Vec2,
+    pub message: RadioMessage,
+}
+
+/// Investigators in [`Chase`] call where the player is when the chase starts, and every `tuning.radio_interval` after that.
+pub fn radio_contact(
+    mut query: Query<(Entity, &Transform, &mut Chase)>,
+    player: Query<(&GridCoords, &Transform, &PlayerVelocity), With<PlayerTag>>,
+    mut calls: EventWriter<RadioCall>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    let Ok((player_coords, player_transform, velocity)) = player.get_single() else {
+        return;
+    };
+
+    for (entity, transform, mut chase) in &mut query {
+        if !chase.is_added() {
+            chase.radio_elapsed += time.delta_seconds();
+            if chase.radio_elapsed < tuning.radio_interval {
+                continue;
+            }
+            chase.radio_elapsed -= tuning.radio_interval;
+        }
+
+        calls.send(RadioCall {
+            caller: entity,
+            location: transform.translation.xy(),
+            message: RadioMessage::Contact {
+                player: *player_coords,
+                // Where the player is going, or at least facing when standing still.
+                heading: velocity
+                    .0
+                    .try_normalize()
+                    .unwrap_or((player_transform.rotation * Vec3::X).xy()),
+            },
+        });
+    }
+}
+
+/// Tile ahead of the player, on one side of its way.
+fn flank_target(
+    grid: &Grid<Tile>,
+    player: &GridCoords,
+    heading: Vec2,
+    side: f32,
+    tuning: &GameTuning,
+) -> GridCoords {
+    let ahead = heading * tuning.flank_distance as f32;
+    let aside = heading.perp() * side * tuning.flank_spread as f32;
+    let offset = (ahead + aside).round().as_ivec2();
+
+    let target = GridLocation::new(player.x + offset.x, player.y + offset.y);
+    if Grid::<Tile>::valid_index(&target) && !grid.occupied(&target) {
+        target.into()
+    } else {
+        *player
+    }
+}
+
+/// Investigators in [`Idle`], [`Wander`], [`Patrol`] or [`Investigate`] within radio range answer calls.
+/// On contact, each one takes a different side ahead of the player, when contact is lost they all search where it was last seen.
+pub fn radio_to_investigating(
+    mut commands: Commands,
+    mut calls: EventReader<RadioCall>,
+    query: Query<
+        (Entity, &Transform, &EnemyTag),
+        Or<(With<Idle>, With<Wander>, With<Patrol>, With<Investigate>)>,
+    >,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    for call in calls.read() {
+        let responders = query.iter().filter(|(entity, transform, tag)| {
+            **tag == EnemyTag::Investigator
+                && *entity != call.caller
+                && transform.translation.xy().distance(call.location) <= tuning.radio_range
+        });
+
+        for (index, (entity, _, _)) in responders.enumerate() {
+            let target = match call.message {
+                RadioMessage::Contact { player, heading } => {
+                    // Straight ahead, then alternating sides.
+                    let side = [0., 1., -1.][index % 3];
+                    flank_target(&grid, &player, heading, side, &tuning)
+                }
+                RadioMessage::ContactLost { last_seen } => last_seen,
+            };
+
+            commands.entity(entity).transition_to(Investigate {
+                target,
+                ..Default::default()
+            });
+        }
+    }
+}
@@ -3,10 +3,10 @@ use bevy_ecs_ldtk::GridCoords;
 use bevy_rand::prelude::{GlobalEntropy, WyRand};
 
 use crate::{
-    config::{MAX_RUN_AWAY_RADIUS, MIN_RUN_AWAY_RADIUS, RUNNING_SPEED},
     grid::{Grid, Tile},
     ldtk::animation::new_animation,
     pathfinding::Path,
+    tuning::GameTuning,
 };
 
 use super::{MovementSpeed, VILLAGER_ANIMATION_FLEE};
@@ -24,17 +24,20 @@ pub fn run_away_on_enter(
     query: Query<(Entity, &GridCoords, &RunAway), Added<RunAway>>,
     grid: Res<Grid<Tile>>,
     mut rng: ResMut<GlobalEntropy<WyRand>>,
+    tuning: Res<GameTuning>,
 ) {
     for (entity, coords, run_away) in &query {
         if let Ok(target) = grid.find_away_from(
             &coords,
             &run_away.player_last_seen,
-            &[MIN_RUN_AWAY_RADIUS, MAX_RUN_AWAY_RADIUS],
+            &[tuning.min_run_away_radius, tuning.max_run_away_radius],
             rng.as_mut(),
         ) {
             if let Ok(path) = grid.path_to(&coords, &target) {
                 commands.entity(entity).insert(path);
-                commands.entity(entity).insert(MovementSpeed(RUNNING_SPEED));
+                commands
+                    .entity(entity)
+                    .insert(MovementSpeed(tuning.running_speed));
             }
         }
 
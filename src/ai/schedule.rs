@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{game_mode::Score, ldtk::entities::EnemyTag, tuning::GameTuning};
+
+/// From this hour of the day, go to a [`PointOfInterest`](crate::ldtk::entities::PointOfInterest) with this tag.
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct ScheduledActivity {
+    pub from_hour: f32, // Between 0 and 24
+    pub tag: String,
+}
+
+impl ScheduledActivity {
+    pub fn new(from_hour: f32, tag: &str) -> Self {
+        ScheduledActivity {
+            from_hour,
+            tag: tag.to_string(),
+        }
+    }
+}
+
+/// What a villager does during the day, instead of wandering around.
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+pub struct VillagerSchedule(pub Vec<ScheduledActivity>);
+
+impl VillagerSchedule {
+    /// Tag of the activity at that hour, the last one of the day keeps going through midnight.
+    pub fn activity_at(&self, hour: f32) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|activity| activity.from_hour <= hour)
+            .max_by(|a, b| a.from_hour.total_cmp(&b.from_hour))
+            .or_else(|| {
+                self.0
+                    .iter()
+                    .max_by(|a, b| a.from_hour.total_cmp(&b.from_hour))
+            })
+            .map(|activity| activity.tag.as_str())
+    }
+}
+
+/// In-game hour, from 0 to 24. Follows the level timer, so it's restored with the [`Score`].
+pub fn hour_of_day(score: &Score, tuning: &GameTuning) -> f32 {
+    (tuning.day_start_hour + score.time() / tuning.day_length * 24.).rem_euclid(24.)
+}
+
+/// Every villager follows the schedule from the tuning file.
+pub fn villager_schedule_added(
+    mut commands: Commands,
+    query: Query<(Entity, &EnemyTag), Added<EnemyTag>>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, tag) in &query {
+        if *tag == EnemyTag::Villager {
+            commands
+                .entity(entity)
+                .insert(VillagerSchedule(tuning.villager_schedule.clone()));
+        }
+    }
+}
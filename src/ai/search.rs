@@ -0,0 +1,177 @@
+// Search
+// Where investigators already looked, shared between them so they split the area.
+// Tiles around the investigated location are picked by priority: hidding spots first, then corners, then open tiles.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_ecs_ldtk::{
+    utils::{grid_coords_to_translation, translation_to_grid_coords},
+    GridCoords,
+};
+
+use crate::{
+    config::{SEARCH_CORNER_PRIORITY, SEARCH_HIDDING_SPOT_PRIORITY, TILE_SIZE},
+    grid::{Grid, GridLocation, Tile},
+    ldtk::entities::{hidding_spot::HiddingSpotExit, Aim},
+    light_map::LightMap,
+    pathfinding::neumann_neighbors,
+    player_controller::is_location_visible,
+    tuning::GameTuning,
+};
+
+use super::Investigate;
+
+/// When each tile was last searched, in seconds since startup.
+#[derive(Resource, Default)]
+pub struct SearchMap {
+    searched_at: HashMap<GridCoords, f32>,
+}
+
+impl SearchMap {
+    pub fn is_searched(&self, coords: &GridCoords, now: f32, memory: f32) -> bool {
+        self.searched_at
+            .get(coords)
+            .is_some_and(|searched_at| now - searched_at < memory)
+    }
+
+    pub fn mark(&mut self, coords: GridCoords, now: f32) {
+        self.searched_at.insert(coords, now);
+    }
+}
+
+/// Walls on both a horizontal and a vertical side.
+fn is_corner(grid: &Grid<Tile>, coords: &GridCoords) -> bool {
+    let occupied = |x: i32, y: i32| grid.occupied(&GridLocation::new(coords.x + x, coords.y + y));
+
+    (occupied(-1, 0) || occupied(1, 0)) && (occupied(0, -1) || occupied(0, 1))
+}
+
+fn distance(from: &GridCoords, to: &GridCoords) -> u32 {
+    GridLocation::from(*from).distance(&GridLocation::from(*to)) as u32
+}
+
+/// What [`next_search_tile`] knows about the search.
+pub struct SearchContext<'a> {
+    pub search_map: &'a SearchMap,
+    /// Tiles other investigators are on their way to search.
+    pub claimed: &'a [GridCoords],
+    /// Where hidding spots are searched from.
+    pub hidding_spots: &'a [GridCoords],
+    /// In seconds since startup.
+    pub now: f32,
+    pub tuning: &'a GameTuning,
+}
+
+/// Best tile to search next, among the tiles reachable within `tuning.investigating_radius` steps of `origin`.
+/// Close tiles are preferred, and tiles around the ones `claimed` by other investigators are left to them.
+pub fn next_search_tile(
+    grid: &Grid<Tile>,
+    origin: &GridCoords,
+    from: &GridCoords,
+    context: &SearchContext,
+) -> Option<GridCoords> {
+    let SearchContext {
+        search_map,
+        claimed,
+        hidding_spots,
+        now,
+        tuning,
+    } = context;
+    let mut best: Option<(f32, GridCoords)> = None;
+
+    let mut visited = HashSet::from([*origin]);
+    let mut queue = VecDeque::from([(*origin, 0)]);
+
+    while let Some((coords, steps)) = queue.pop_front() {
+        if steps < tuning.investigating_radius {
+            for neighbor in neumann_neighbors(grid, &coords) {
+                if visited.insert(neighbor) {
+                    queue.push_back((neighbor, steps + 1));
+                }
+            }
+        }
+
+        if coords == *from
+            || search_map.is_searched(&coords, *now, tuning.search_memory_time)
+            || claimed
+                .iter()
+                .any(|claim| distance(claim, &coords) <= tuning.search_claim_radius)
+        {
+            continue;
+        }
+
+        let priority = if hidding_spots.contains(&coords) {
+            SEARCH_HIDDING_SPOT_PRIORITY
+        } else if is_corner(grid, &coords) {
+            SEARCH_CORNER_PRIORITY
+        } else {
+            1.
+        };
+        let score = priority / (1 + distance(from, &coords)) as f32;
+
+        if best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, coords));
+        }
+    }
+
+    best.map(|(_, coords)| coords)
+}
+
+/// Tiles an investigator can see around itself are searched, as far as its light and view allow.
+/// Hidding spots aren't, they need to be checked up close.
+pub fn mark_searched(
+    query: Query<(&Transform, &Aim), With<Investigate>>,
+    hidding_spots: Query<&HiddingSpotExit>,
+    mut search_map: ResMut<SearchMap>,
+    grid: Res<Grid<Tile>>,
+    light_map: Res<LightMap>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    let now = time.elapsed_seconds();
+    let radius = tuning.search_sight_radius as i32;
+    // Up to the center of the farthest tiles.
+    let range = (radius as f32 + 0.5) * TILE_SIZE.x as f32;
+    let hidding_spots: Vec<GridCoords> = hidding_spots
+        .iter()
+        .map(|exit| translation_to_grid_coords(exit.0, TILE_SIZE))
+        .collect();
+
+    for (transform, aim) in &query {
+        let location = transform.translation.xy();
+        let center = translation_to_grid_coords(location, TILE_SIZE);
+
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                let coords = GridCoords::new(center.x + x, center.y + y);
+                if !Grid::<Tile>::valid_index(&GridLocation::from(coords))
+                    || hidding_spots.contains(&coords)
+                {
+                    continue;
+                }
+
+                let tile = grid_coords_to_translation(coords, TILE_SIZE);
+                if is_location_visible(
+                    tile,
+                    location,
+                    *aim,
+                    range,
+                    tuning.investigator_view_half_angle,
+                    &light_map,
+                    &grid,
+                ) {
+                    search_map.mark(coords, now);
+                }
+            }
+        }
+    }
+}
+
+/// Forget everything searched in the previous level.
+pub fn reset_search_map(mut commands: Commands) {
+    commands.insert_resource(SearchMap::default());
+}
@@ -0,0 +1,323 @@
+// State machine
+// Every AI state is a component, an enemy has exactly one of them.
+// Changing state goes through `transition_to`, which removes the previous state, checks the transition
+// is declared for the archetype of the enemy, and logs it.
+// Systems deciding transitions don't see each other's, so when several land on the same frame the most urgent one wins.
+
+use bevy::{
+    core::FrameCount,
+    ecs::system::{EntityCommand, EntityCommands},
+    prelude::*,
+};
+
+use crate::ldtk::entities::EnemyTag;
+
+use super::{Chase, Dead, Idle, Investigate, Patrol, RunAway, TalkToInvestigator, Visit, Wander};
+
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StateKind {
+    Idle,
+    Wander,
+    Patrol,
+    Visit,
+    Investigate,
+    Chase,
+    RunAway,
+    TalkToInvestigator,
+    Dead,
+}
+
+impl StateKind {
+    /// Only a more urgent state can replace one entered during the same frame.
+    fn urgency(self) -> u8 {
+        match self {
+            StateKind::Idle | StateKind::Wander | StateKind::Patrol | StateKind::Visit => 0,
+            StateKind::Investigate => 1,
+            StateKind::RunAway | StateKind::TalkToInvestigator => 2,
+            StateKind::Chase => 3,
+            StateKind::Dead => 4,
+        }
+    }
+}
+
+/// Component used as an AI state.
+pub trait AiState: Component {
+    const KIND: StateKind;
+}
+
+/// The enemy is in one of the AI states.
+/// Kept in sync by hooks on every state, so queries can't forget a state.
+#[derive(Component)]
+pub struct HasAiState;
+
+macro_rules! ai_states {
+    ($($state:ident),*) => {
+        $(impl AiState for $state {
+            const KIND: StateKind = StateKind::$state;
+        })*
+
+        /// The state the entity is in, if any.
+        fn current_state(entity: &EntityWorldMut) -> Option<StateKind> {
+            $(if entity.contains::<$state>() {
+                return Some(StateKind::$state);
+            })*
+            None
+        }
+
+        fn remove_states(entity: &mut EntityWorldMut) {
+            entity.remove::<($($state,)*)>();
+        }
+
+        /// Add and remove [`HasAiState`] along with the states.
+        /// When switching state, the removal is queued before the insertion, so the marker stays.
+        pub fn register_state_hooks(world: &mut World) {
+            $(world
+                .register_component_hooks::<$state>()
+                .on_add(|mut world, entity, _| {
+                    world.commands().entity(entity).try_insert(HasAiState);
+                })
+                .on_remove(|mut world, entity, _| {
+                    world.commands().entity(entity).remove::<HasAiState>();
+                });)*
+        }
+    };
+}
+
+ai_states!(
+    Idle,
+    Wander,
+    Patrol,
+    Visit,
+    Investigate,
+    Chase,
+    RunAway,
+    TalkToInvestigator,
+    Dead
+);
+
+/// For each state, the states it can switch to.
+pub struct StateMachine(&'static [(StateKind, &'static [StateKind])]);
+
+impl StateMachine {
+    pub fn allows(&self, from: StateKind, to: StateKind) -> bool {
+        self.0
+            .iter()
+            .any(|(state, next)| *state == from && next.contains(&to))
+    }
+
+    pub fn for_tag(tag: &EnemyTag) -> &'static StateMachine {
+        match tag {
+            EnemyTag::Investigator => &INVESTIGATOR_STATE_MACHINE,
+            EnemyTag::Villager => &VILLAGER_STATE_MACHINE,
+        }
+    }
+}
+
+pub const INVESTIGATOR_STATE_MACHINE: StateMachine = StateMachine(&[
+    (
+        StateKind::Idle,
+        &[
+            StateKind::Wander,
+            StateKind::Patrol,
+            StateKind::Investigate,
+            StateKind::Chase,
+        ],
+    ),
+    (
+        StateKind::Wander,
+        &[StateKind::Idle, StateKind::Investigate, StateKind::Chase],
+    ),
+    (
+        StateKind::Patrol,
+        &[StateKind::Investigate, StateKind::Chase],
+    ),
+    (
+        StateKind::Investigate,
+        &[
+            StateKind::Idle,
+            StateKind::Patrol,
+            StateKind::Investigate,
+            StateKind::Chase,
+        ],
+    ),
+    (StateKind::Chase, &[StateKind::Idle, StateKind::Investigate]),
+]);
+
+pub const VILLAGER_STATE_MACHINE: StateMachine = StateMachine(&[
+    (
+        StateKind::Idle,
+        &[
+            StateKind::Wander,
+            StateKind::Visit,
+            StateKind::RunAway,
+            StateKind::Dead,
+        ],
+    ),
+    (
+        StateKind::Wander,
+        &[StateKind::Idle, StateKind::RunAway, StateKind::Dead],
+    ),
+    (
+        StateKind::Visit,
+        &[StateKind::Idle, StateKind::RunAway, StateKind::Dead],
+    ),
+    (
+        StateKind::RunAway,
+        &[
+            StateKind::Idle,
+            StateKind::TalkToInvestigator,
+            StateKind::Dead,
+        ],
+    ),
+    (
+        StateKind::TalkToInvestigator,
+        &[StateKind::RunAway, StateKind::Dead],
+    ),
+]);
+
+/// Frame of the last transition of an enemy.
+#[derive(Component)]
+struct LastTransition {
+    frame: u32,
+    to: StateKind,
+}
+
+/// Replace the current state of an enemy, if the [`StateMachine`] of its [`EnemyTag`] allows it.
+/// During the same frame, only a more urgent state replaces the one just entered.
+struct TransitionTo<S: AiState>(S);
+
+impl<S: AiState> EntityCommand for TransitionTo<S> {
+    fn apply(self, id: Entity, world: &mut World) {
+        let frame = world
+            .get_resource::<FrameCount>()
+            .map(|frame_count| frame_count.0);
+        let Some(mut entity) = world.get_entity_mut(id) else {
+            return;
+        };
+        let Some(tag) = entity.get::<EnemyTag>() else {
+            warn!("{:?} isn't an enemy, can't switch to {:?}", id, S::KIND);
+            return;
+        };
+
+        if let Some(last) = entity.get::<LastTransition>() {
+            if Some(last.frame) == frame && S::KIND.urgency() <= last.to.urgency() {
+                debug!(
+                    "{:?}: {:?} ignored, already switched to {:?} this frame",
+                    id,
+                    S::KIND,
+                    last.to
+                );
+                return;
+            }
+        }
+
+        let state_machine = StateMachine::for_tag(tag);
+        let from = current_state(&entity);
+
+        if let Some(from) = from {
+            if !state_machine.allows(from, S::KIND) {
+                warn!("{:?} can't switch from {:?} to {:?}", id, from, S::KIND);
+                return;
+            }
+        }
+
+        debug!("{:?}: {:?} -> {:?}", id, from, S::KIND);
+
+        remove_states(&mut entity);
+        entity.insert(self.0);
+        if let Some(frame) = frame {
+            entity.insert(LastTransition { frame, to: S::KIND });
+        }
+    }
+}
+
+pub trait TransitionExt {
+    /// Switch to another AI state, see [`StateMachine`].
+    fn transition_to<S: AiState>(&mut self, state: S) -> &mut Self;
+}
+
+impl TransitionExt for EntityCommands<'_> {
+    fn transition_to<S: AiState>(&mut self, state: S) -> &mut Self {
+        self.add(TransitionTo(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::CommandQueue;
+    use bevy_ecs_ldtk::GridCoords;
+
+    use super::*;
+
+    fn investigator(world: &mut World) -> Entity {
+        world.spawn((EnemyTag::Investigator, Idle::default())).id()
+    }
+
+    fn chase(target: Entity) -> Chase {
+        Chase {
+            target,
+            player_last_seen: GridCoords::default(),
+            player_velocity: Vec2::ZERO,
+            predicted: GridCoords::default(),
+            radio_elapsed: 0.,
+        }
+    }
+
+    /// Apply the transitions queued by `queue_transitions`, all during the current frame.
+    fn apply(world: &mut World, queue_transitions: impl FnOnce(&mut Commands)) {
+        let mut queue = CommandQueue::default();
+        queue_transitions(&mut Commands::new(&mut queue, world));
+        queue.apply(world);
+    }
+
+    #[test]
+    fn same_frame_less_urgent_transition_is_ignored() {
+        let mut world = World::new();
+        world.insert_resource(FrameCount(0));
+        let player = world.spawn_empty().id();
+        let enemy = investigator(&mut world);
+
+        apply(&mut world, |commands| {
+            commands.entity(enemy).transition_to(chase(player));
+            commands.entity(enemy).transition_to(Investigate::default());
+        });
+
+        assert!(world.entity(enemy).contains::<Chase>());
+        assert!(!world.entity(enemy).contains::<Investigate>());
+    }
+
+    #[test]
+    fn same_frame_more_urgent_transition_wins() {
+        let mut world = World::new();
+        world.insert_resource(FrameCount(0));
+        let player = world.spawn_empty().id();
+        let enemy = investigator(&mut world);
+
+        apply(&mut world, |commands| {
+            commands.entity(enemy).transition_to(Investigate::default());
+            commands.entity(enemy).transition_to(chase(player));
+        });
+
+        assert!(world.entity(enemy).contains::<Chase>());
+        assert!(!world.entity(enemy).contains::<Investigate>());
+    }
+
+    #[test]
+    fn next_frame_transition_applies() {
+        let mut world = World::new();
+        world.insert_resource(FrameCount(0));
+        let player = world.spawn_empty().id();
+        let enemy = investigator(&mut world);
+
+        apply(&mut world, |commands| {
+            commands.entity(enemy).transition_to(chase(player));
+        });
+        world.resource_mut::<FrameCount>().0 += 1;
+        apply(&mut world, |commands| {
+            commands.entity(enemy).transition_to(Investigate::default());
+        });
+
+        assert!(world.entity(enemy).contains::<Investigate>());
+        assert!(!world.entity(enemy).contains::<Chase>());
+    }
+}
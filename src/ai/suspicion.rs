@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::{ldtk::entities::EnemyTag, rendering::PIXEL_PERFECT_LAYERS};
+
+const INDICATOR_WIDTH: f32 = 12.; // In world units, when full
+const INDICATOR_HEIGHT: f32 = 2.; // In world units
+const INDICATOR_CALM_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const INDICATOR_ALERT_COLOR: Color = Color::srgb(0.9, 0.1, 0.1);
+
+/// How sure an enemy is that it saw the player, from 0 to 1.
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+pub struct Suspicion(pub f32);
+
+/// Bar above an enemy showing its [`Suspicion`].
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+pub struct SuspicionIndicator;
+
+/// Every enemy starts calm, with a hidden indicator.
+pub fn enemy_added(mut commands: Commands, query: Query<Entity, Added<EnemyTag>>) {
+    for entity in &query {
+        let indicator = commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: INDICATOR_CALM_COLOR,
+                        custom_size: Some(Vec2::new(0., INDICATOR_HEIGHT)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(Vec3::new(0., 14., 100.)),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                SuspicionIndicator,
+                PIXEL_PERFECT_LAYERS,
+            ))
+            .id();
+
+        commands
+            .entity(entity)
+            .insert(Suspicion::default())
+            .add_child(indicator);
+    }
+}
+
+pub fn update_indicators(
+    query: Query<(&Suspicion, &Children), Changed<Suspicion>>,
+    mut indicators: Query<(&mut Sprite, &mut Visibility), With<SuspicionIndicator>>,
+) {
+    for (suspicion, children) in &query {
+        for child in children {
+            let Ok((mut sprite, mut visibility)) = indicators.get_mut(*child) else {
+                continue;
+            };
+
+            *visibility = if suspicion.0 > 0. {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+
+            sprite.custom_size = Some(Vec2::new(INDICATOR_WIDTH * suspicion.0, INDICATOR_HEIGHT));
+            sprite.color = INDICATOR_CALM_COLOR.mix(&INDICATOR_ALERT_COLOR, suspicion.0);
+        }
+    }
+}
@@ -1,23 +1,36 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 use bevy_ecs_ldtk::GridCoords;
 
 use crate::{
-    config::RUNNING_SPEED,
-    grid::{Grid, Tile},
     ldtk::animation::new_animation,
-    pathfinding::Path,
+    pathfinding::{Path, PathRequest, PathUnreachable},
+    tuning::GameTuning,
 };
 
-use super::{run_away::RunAway, MovementSpeed, VILLAGER_ANIMATION_FLEE};
+use super::{
+    run_away::RunAway, state_machine::TransitionExt, MovementSpeed, VILLAGER_ANIMATION_FLEE,
+};
 
 #[derive(Reflect, Clone, Component)]
-#[reflect(Component)]
+#[reflect(Component, MapEntities)]
 #[component(storage = "SparseSet")]
 pub struct TalkToInvestigator {
     pub investigator: Entity,
     pub player_last_seen: GridCoords,
 }
 
+impl MapEntities for TalkToInvestigator {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.investigator = entity_mapper.map_entity(self.investigator);
+    }
+}
+
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
 #[component(storage = "SparseSet")]
@@ -26,9 +39,12 @@ pub struct HasTakedToInvestigator;
 pub fn talk_to_investigator_on_enter(
     mut commands: Commands,
     query: Query<Entity, Added<TalkToInvestigator>>,
+    tuning: Res<GameTuning>,
 ) {
     for entity in &query {
-        commands.entity(entity).insert(MovementSpeed(RUNNING_SPEED));
+        commands
+            .entity(entity)
+            .insert(MovementSpeed(tuning.running_speed));
         commands
             .entity(entity)
             .insert(new_animation(VILLAGER_ANIMATION_FLEE));
@@ -38,26 +54,22 @@ pub fn talk_to_investigator_on_enter(
 pub fn talk_to_investigator_update(
     mut commands: Commands,
     coords: Query<&GridCoords, Without<TalkToInvestigator>>,
-    mut query: Query<(Entity, &GridCoords, &TalkToInvestigator)>,
-    grid: Res<Grid<Tile>>,
+    query: Query<(Entity, &TalkToInvestigator, Option<&PathUnreachable>)>,
 ) {
-    for (entity, entity_coords, talk) in &mut query {
+    for (entity, talk, unreachable) in &query {
         let Ok(target_coords) = coords.get(talk.investigator) else {
             continue;
         };
 
-        // Calculate path to target
-        let path = grid.path_to(&entity_coords, &target_coords);
-
-        // if found a valid path to target
-        if let Ok(path) = path {
-            commands.entity(entity).insert(path);
+        if !PathUnreachable::is_goal(unreachable, target_coords) {
+            commands.entity(entity).insert(PathRequest {
+                goal: *target_coords,
+            });
             continue;
         }
 
         // Could not find a path to the investigator, abandon trying and go back to running away.
-        commands.entity(entity).remove::<TalkToInvestigator>();
-        commands.entity(entity).insert(RunAway {
+        commands.entity(entity).transition_to(RunAway {
             player_last_seen: talk.player_last_seen,
         });
     }
@@ -69,6 +81,9 @@ pub fn talk_to_investigator_on_exit(
 ) {
     for entity in query.read() {
         commands.entity(entity).remove::<Path>();
+        commands
+            .entity(entity)
+            .remove::<(PathRequest, PathUnreachable)>();
         commands.entity(entity).remove::<MovementSpeed>();
     }
 }
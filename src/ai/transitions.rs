@@ -1,74 +1,84 @@
-use std::time::Duration;
-
 use bevy::prelude::*;
 use bevy_ecs_ldtk::GridCoords;
+use bevy_rand::prelude::{GlobalEntropy, WyRand};
 use bevy_rapier2d::plugin::RapierContext;
+use rand_core::RngCore;
 
 use crate::{
-    ldtk::entities::{noise_maker::NoiseMakerTriggered, player::PlayerTag, Aim, EnemyTag},
+    game_mode::Score,
+    grid::{Grid, Tile},
+    ldtk::entities::{
+        player::{PlayerTag, PlayerVelocity},
+        Aim, EnemyTag, PatrolRoute, PointOfInterest,
+    },
+    light_map::LightMap,
     pathfinding::Path,
-    player_controller::{is_player_visible, PlayerIsHidding},
+    player_controller::{is_location_visible, is_player_visible, PlayerIsHidding, SeenHidding},
     states::PlayingState,
+    tuning::GameTuning,
 };
 
 use super::{
-    Chase, Chased, Dead, HasTakedToInvestigator, Idle, Investigate, RunAway, TalkToInvestigator,
-    Wander,
+    chase::predict_location,
+    schedule::hour_of_day,
+    state_machine::{HasAiState, TransitionExt},
+    Chase, Chased, Dead, HasTakedToInvestigator, Idle, Investigate, Patrol, RadioCall,
+    RadioMessage, RunAway, Suspicion, TalkToInvestigator, VillagerSchedule, Visit, Wander,
 };
 
-use crate::config::*;
-
 /// Default [`Idle`] if no AI taks found for enemy entity.
 pub fn nothing_to_idle(
     mut commands: Commands,
-    query: Query<
-        Entity,
-        (
-            With<EnemyTag>,
-            Without<Chase>,
-            Without<Idle>,
-            Without<Investigate>,
-            Without<RunAway>,
-            Without<Wander>,
-            Without<Dead>,
-        ),
-    >,
+    query: Query<Entity, (With<EnemyTag>, Without<HasAiState>)>,
 ) {
     for entity in &query {
-        commands.entity(entity).insert(Idle::default());
+        commands.entity(entity).transition_to(Idle::default());
     }
 }
 
-/// In any [`Idle`], [`Investigate`] or [`Wander`], and the player is nearby and in the field of vision of an Enemy, either [`Chase`] or [`RunAway`].
+/// In any [`Idle`], [`Investigate`], [`Wander`], [`Patrol`] or [`Visit`], the [`Suspicion`] of an enemy builds while the player is in its field of vision, and decays otherwise.
+/// Investigators [`Investigate`] past the suspicion threshold, and at full suspicion either [`Chase`] or [`RunAway`].
 pub fn notice_player(
     mut commands: Commands,
-    player: Query<(Entity, &GridCoords, &Transform), (With<PlayerTag>, Without<PlayerIsHidding>)>,
-    query: Query<(
+    player: Query<
+        (Entity, &GridCoords, &Transform, &PlayerVelocity),
+        (With<PlayerTag>, Without<PlayerIsHidding>),
+    >,
+    mut query: Query<(
         Entity,
         &Transform,
         &Aim,
         &EnemyTag,
-        AnyOf<(&Idle, &Investigate, &Wander)>,
+        &mut Suspicion,
+        AnyOf<(&Idle, &Investigate, &Wander, &Patrol, &Visit)>,
     )>,
+    light_map: Res<LightMap>,
     rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
 ) {
-    if let Ok((player, player_coords, player_transform)) = player.get_single() {
-        for (entity, entity_transform, aim, tag, _) in &query {
-            let distance_threshold = match tag {
-                EnemyTag::Investigator => INVESTIGATOR_VIEW_RANGE,
-                EnemyTag::Villager => VILLAGERS_VIEW_RANGE,
-            };
+    let player = player.get_single().ok();
+
+    for (entity, entity_transform, aim, tag, mut suspicion, (idle, _, wander, patrol, _)) in
+        &mut query
+    {
+        let distance_threshold = match tag {
+            EnemyTag::Investigator => tuning.investigator_view_range,
+            EnemyTag::Villager => tuning.villagers_view_range,
+        };
 
-            let angle_threshold = match tag {
-                EnemyTag::Investigator => INVESTIGATOR_VIEW_HALF_ANGLE,
-                EnemyTag::Villager => VILLAGERS_VIEW_HALF_ANGLE,
-            };
+        let angle_threshold = match tag {
+            EnemyTag::Investigator => tuning.investigator_view_half_angle,
+            EnemyTag::Villager => tuning.villagers_view_half_angle,
+        };
 
+        let enemy_location = entity_transform.translation.xy();
+
+        // Check if player is visible, and how well.
+        let seen = player.and_then(|(player, player_coords, player_transform, velocity)| {
             let player_location = player_transform.translation.xy();
-            let enemy_location = entity_transform.translation.xy();
 
-            // Check if player is visible.
-            let result = is_player_visible(
+            is_player_visible(
                 player,
                 entity,
                 player_location,
@@ -76,41 +86,105 @@ pub fn notice_player(
                 *aim,
                 distance_threshold,
                 angle_threshold,
+                &light_map,
                 &rapier_context,
-            );
+            )
+            .then(|| {
+                // Relative to the view range in this light.
+                let view_range = distance_threshold * light_map.visibility_at(player_location);
+                let closeness = 1. - player_location.distance(enemy_location) / view_range;
+                let centeredness = 1.
+                    - aim.0.angle_between(player_location - enemy_location).abs()
+                        / angle_threshold.to_radians();
+
+                // Even at the edge of the view, the player is noticed eventually.
+                let quality = (0.2 + 0.8 * closeness.clamp(0., 1.))
+                    * (0.2 + 0.8 * centeredness.clamp(0., 1.));
+                (player, *player_coords, velocity.0, quality)
+            })
+        });
 
-            if result {
-                // Removing inexisting component seems fine (nothing is screaming at me).
-                commands.entity(entity).remove::<Idle>();
-                commands.entity(entity).remove::<Investigate>();
-                commands.entity(entity).remove::<Wander>();
-
-                match tag {
-                    // If Enemy is an Investigator, chase the player.
-                    EnemyTag::Investigator => {
-                        commands.entity(entity).insert(Chase {
-                            target: player,
-                            player_last_seen: *player_coords,
-                        });
-                        commands.entity(player).insert(Chased);
-                    }
-                    // If enemy is a Villager, run away from player.
-                    EnemyTag::Villager => {
-                        commands.entity(entity).insert(RunAway {
-                            player_last_seen: *player_coords,
-                        });
-                    }
+        let Some((player, player_coords, player_velocity, quality)) = seen else {
+            if suspicion.0 > 0. {
+                suspicion.0 =
+                    (suspicion.0 - tuning.suspicion_decay_rate * time.delta_seconds()).max(0.);
+            }
+            continue;
+        };
+
+        suspicion.0 =
+            (suspicion.0 + tuning.suspicion_build_rate * quality * time.delta_seconds()).min(1.);
+
+        if suspicion.0 >= 1. {
+            match tag {
+                // If Enemy is an Investigator, chase the player.
+                EnemyTag::Investigator => {
+                    commands.entity(entity).transition_to(Chase {
+                        target: player,
+                        player_last_seen: player_coords,
+                        player_velocity,
+                        predicted: player_coords,
+                        radio_elapsed: 0.,
+                    });
+                    commands.entity(player).insert(Chased);
+                }
+                // If enemy is a Villager, run away from player.
+                EnemyTag::Villager => {
+                    commands.entity(entity).transition_to(RunAway {
+                        player_last_seen: player_coords,
+                    });
                 }
             }
+        } else if suspicion.0 >= tuning.suspicion_investigate_threshold
+            && *tag == EnemyTag::Investigator
+            && (idle.is_some() || wander.is_some() || patrol.is_some())
+        {
+            // Something moved over there, go have a look.
+            commands.entity(entity).transition_to(Investigate {
+                target: player_coords,
+                ..Default::default()
+            });
         }
     }
 }
 
+/// Investigators watching the player getting into a hidding spot will know where to look.
+pub fn notice_player_hidding(
+    mut commands: Commands,
+    player: Query<(Entity, &Transform), Added<PlayerIsHidding>>,
+    query: Query<(&Transform, &Aim, &EnemyTag), (Without<Dead>, Without<PlayerTag>)>,
+    light_map: Res<LightMap>,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    let Ok((player, player_transform)) = player.get_single() else {
+        return;
+    };
+
+    let seen = query.iter().any(|(transform, aim, tag)| {
+        *tag == EnemyTag::Investigator
+            && is_location_visible(
+                player_transform.translation.xy(),
+                transform.translation.xy(),
+                *aim,
+                tuning.investigator_view_range,
+                tuning.investigator_view_half_angle,
+                &light_map,
+                &grid,
+            )
+    });
+
+    if seen {
+        commands.entity(player).insert(SeenHidding);
+    }
+}
+
 /// If any [`RunAway`] find an investigator on their path, swtich to going to talk to them.
 pub fn running_away_to_talk_to_investigator(
     mut commands: Commands,
     query: Query<(Entity, &Transform, &Aim, &RunAway), Without<HasTakedToInvestigator>>,
     query2: Query<(Entity, &Transform, &EnemyTag), Without<Chase>>,
+    tuning: Res<GameTuning>,
 ) {
     for (entity, transform, aim, run_away) in &query {
         for (investigator, investigator_transform, tag) in &query2 {
@@ -119,16 +193,14 @@ pub fn running_away_to_talk_to_investigator(
                 let entity_locaton = transform.translation.xy();
 
                 // Check if investigator is withing range.
-                if investigator_location.distance(entity_locaton) < VILLAGERS_VIEW_RANGE {
+                if investigator_location.distance(entity_locaton) < tuning.villagers_view_range {
                     // Check if investigator is within view.
                     if aim
                         .0
                         .dot((investigator_location - entity_locaton).normalize())
-                        < VILLAGERS_VIEW_HALF_ANGLE.cos()
+                        < tuning.villagers_view_half_angle.cos()
                     {
-                        commands.entity(entity).remove::<RunAway>();
-
-                        commands.entity(entity).insert(TalkToInvestigator {
+                        commands.entity(entity).transition_to(TalkToInvestigator {
                             investigator,
                             player_last_seen: run_away.player_last_seen,
                         });
@@ -144,6 +216,7 @@ pub fn talk_to_investigator_to_running_away(
     mut commands: Commands,
     transforms: Query<&Transform, Without<TalkToInvestigator>>,
     query: Query<(Entity, &Transform, &TalkToInvestigator)>,
+    tuning: Res<GameTuning>,
 ) {
     for (entity, entity_transform, talk) in &query {
         if let Ok(investigator_transform) = transforms.get(talk.investigator) {
@@ -151,24 +224,18 @@ pub fn talk_to_investigator_to_running_away(
                 .translation
                 .xy()
                 .distance(entity_transform.translation.xy())
-                <= INTERACTION_DISTANCE * 1.5
+                <= tuning.interaction_distance * 1.5
             {
                 commands
                     .entity(talk.investigator)
-                    .remove::<Idle>()
-                    .remove::<Investigate>()
-                    .remove::<Wander>();
-
-                commands.entity(talk.investigator).insert(Investigate {
-                    target: talk.player_last_seen,
-                    ..Default::default()
-                });
-
-                commands.entity(entity).remove::<TalkToInvestigator>();
+                    .transition_to(Investigate {
+                        target: talk.player_last_seen,
+                        ..Default::default()
+                    });
 
                 commands
                     .entity(entity)
-                    .insert(RunAway {
+                    .transition_to(RunAway {
                         player_last_seen: talk.player_last_seen,
                     })
                     .insert(HasTakedToInvestigator);
@@ -177,14 +244,57 @@ pub fn talk_to_investigator_to_running_away(
     }
 }
 
-/// After the timer for [`Idle`] expires switch to [`Idle`].
-pub fn idle_to_wandering(mut commands: Commands, query: Query<(Entity, &Idle)>) {
-    for (entity, idle) in &query {
-        if idle.start.elapsed() >= Duration::from_secs(IDLING_TIME) {
-            commands.entity(entity).remove::<Idle>();
+/// After the timer for [`Idle`] expires switch to [`Patrol`] if there is a [`PatrolRoute`],
+/// [`Visit`] a point of interest for the current activity of the [`VillagerSchedule`], or [`Wander`] otherwise.
+pub fn idle_to_wandering(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &GridCoords,
+        &mut Idle,
+        Option<&PatrolRoute>,
+        Option<&VillagerSchedule>,
+    )>,
+    points_of_interest: Query<(&GridCoords, &PointOfInterest)>,
+    score: Res<Score>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    let hour = hour_of_day(&score, &tuning);
+
+    for (entity, coords, mut idle, route, schedule) in &mut query {
+        if idle.elapsed.tick(time.delta()).elapsed_secs() < tuning.idling_time {
+            continue;
+        }
 
-            commands.entity(entity).insert(Wander);
+        if let Some(route) = route.filter(|route| !route.0.is_empty()) {
+            commands
+                .entity(entity)
+                .transition_to(Patrol::closest(route, coords));
+            continue;
         }
+
+        let candidates: Vec<(&GridCoords, &PointOfInterest)> =
+            match schedule.and_then(|schedule| schedule.activity_at(hour)) {
+                Some(activity) => points_of_interest
+                    .iter()
+                    .filter(|(_, point)| point.tags.iter().any(|tag| tag == activity))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+        if candidates.is_empty() {
+            commands.entity(entity).transition_to(Wander);
+            continue;
+        }
+
+        let (target, point) = candidates[rng.next_u32() as usize % candidates.len()];
+        commands.entity(entity).transition_to(Visit {
+            target: *target,
+            facing: point.facing,
+            elapsed: None,
+        });
     }
 }
 
@@ -194,50 +304,50 @@ pub fn wandering_to_idle(
     query: Query<Entity, (With<Wander>, Without<Path>)>,
 ) {
     for entity in &query {
-        commands.entity(entity).remove::<Wander>();
-
-        commands.entity(entity).insert(Idle::default());
+        commands.entity(entity).transition_to(Idle::default());
     }
 }
 
-/// After reaching the [`RunAway`].target, switch to [`Idle`].
-pub fn run_away_to_idle(
+/// After spending some time at the [`Visit`].target, switch to [`Idle`], which picks the next activity.
+pub fn visiting_to_idle(
     mut commands: Commands,
-    query: Query<Entity, (With<RunAway>, Without<Path>)>,
+    query: Query<(Entity, &Visit)>,
+    tuning: Res<GameTuning>,
 ) {
-    for entity in &query {
-        commands.entity(entity).remove::<RunAway>();
-
-        commands.entity(entity).insert(Idle::default());
+    for (entity, visit) in &query {
+        if visit
+            .elapsed
+            .as_ref()
+            .is_some_and(|elapsed| elapsed.elapsed_secs() >= tuning.activity_time)
+        {
+            commands.entity(entity).transition_to(Idle::default());
+        }
     }
 }
 
-/// If the player triggered an interactible nearby, go [`Investigate`].
-pub fn idle_or_wandering_to_investigating(
+/// After reaching the [`RunAway`].target, switch to [`Idle`].
+pub fn run_away_to_idle(
     mut commands: Commands,
-    query: Query<(Entity, &NoiseMakerTriggered), Or<(With<Idle>, With<Wander>)>>,
+    query: Query<Entity, (With<RunAway>, Without<Path>)>,
 ) {
-    for (entity, interactible) in &query {
-        commands.entity(entity).remove::<Idle>();
-        commands.entity(entity).remove::<Wander>();
-        commands.entity(entity).remove::<NoiseMakerTriggered>();
-
-        commands.entity(entity).insert(Investigate {
-            target: interactible.0,
-            ..Default::default()
-        });
+    for entity in &query {
+        commands.entity(entity).transition_to(Idle::default());
     }
 }
 
-/// If lost visual on player during [`Chase`], go [`Investigate`] last known location.
+/// If lost visual on player during [`Chase`], go [`Investigate`] where the player was heading, and call the squad to search there too.
 pub fn chasing_to_investigating(
     mut commands: Commands,
-    player: Query<(Entity, &GridCoords, &Transform, Option<&PlayerIsHidding>), With<PlayerTag>>,
-    query: Query<(Entity, &Transform, &Aim), With<Chase>>,
+    player: Query<(Entity, &Transform, Option<&PlayerIsHidding>), With<PlayerTag>>,
+    query: Query<(Entity, &Transform, &Aim, &Chase)>,
+    mut calls: EventWriter<RadioCall>,
+    grid: Res<Grid<Tile>>,
+    light_map: Res<LightMap>,
     rapier_context: Res<RapierContext>,
+    tuning: Res<GameTuning>,
 ) {
-    for (entity, entity_transform, aim) in &query {
-        let Ok((player, target_coords, target_transform, hidding)) = player.get_single() else {
+    for (entity, entity_transform, aim, chase) in &query {
+        let Ok((player, target_transform, hidding)) = player.get_single() else {
             continue;
         };
 
@@ -252,18 +362,35 @@ pub fn chasing_to_investigating(
                 target_translate,
                 entity_translate,
                 *aim,
-                INVESTIGATOR_VIEW_RANGE * 1.3,
-                INVESTIGATOR_VIEW_HALF_ANGLE,
+                tuning.investigator_view_range * 1.3,
+                tuning.investigator_view_half_angle,
+                &light_map,
                 &rapier_context,
             );
 
         // If still visible, update last seen coordinates, otherwise, swtich to Investigate.
         if !result {
-            commands.entity(entity).remove::<Chase>();
             commands.entity(player).remove::<Chased>();
 
-            commands.entity(entity).insert(Investigate {
-                target: *target_coords,
+            // Keep going the way the player was last seen moving.
+            let predicted = predict_location(
+                &grid,
+                &chase.player_last_seen,
+                chase.player_velocity,
+                tuning.chase_prediction_time,
+            );
+
+            // Get the squad to search with us.
+            calls.send(RadioCall {
+                caller: entity,
+                location: entity_translate,
+                message: RadioMessage::ContactLost {
+                    last_seen: predicted,
+                },
+            });
+
+            commands.entity(entity).transition_to(Investigate {
+                target: predicted,
                 ..Default::default()
             });
         }
@@ -276,6 +403,7 @@ pub fn chasing_to_killing(
     query: Query<(Entity, &Transform), With<Chase>>,
     player: Query<(Entity, &Transform), With<PlayerTag>>,
     mut next_state: ResMut<NextState<PlayingState>>,
+    tuning: Res<GameTuning>,
 ) {
     if let Ok((_, player_transform)) = player.get_single() {
         for (entity, transform) in &query {
@@ -283,9 +411,9 @@ pub fn chasing_to_killing(
                 .translation
                 .xy()
                 .distance(player_transform.translation.xy())
-                <= INTERACTION_DISTANCE
+                <= tuning.interaction_distance
             {
-                commands.entity(entity).remove::<Chase>();
+                commands.entity(entity).transition_to(Idle::default());
 
                 // TODO: Add death
                 warn!("Player died!");
@@ -295,13 +423,21 @@ pub fn chasing_to_killing(
     }
 }
 
-/// After the [`Investigate`] timer ran out, switch back to [`Idle`].
-pub fn investigating_to_idle(mut commands: Commands, query: Query<(Entity, &Investigate)>) {
-    for (entity, investigate) in &query {
-        if investigate.start.elapsed() >= Duration::from_secs(INVESTIGATING_TIME) {
-            commands.entity(entity).remove::<Investigate>();
-
-            commands.entity(entity).insert(Idle::default());
+/// After the [`Investigate`] timer ran out, switch back to [`Idle`], or resume the [`Patrol`] if there is a [`PatrolRoute`].
+pub fn investigating_to_idle(
+    mut commands: Commands,
+    mut query: Query<(Entity, &GridCoords, &mut Investigate, Option<&PatrolRoute>)>,
+    time: Res<Time>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, coords, mut investigate, route) in &mut query {
+        if investigate.elapsed.tick(time.delta()).elapsed_secs() >= tuning.investigating_time {
+            match route.filter(|route| !route.0.is_empty()) {
+                Some(route) => commands
+                    .entity(entity)
+                    .transition_to(Patrol::closest(route, coords)),
+                None => commands.entity(entity).transition_to(Idle::default()),
+            };
         }
     }
 }
@@ -0,0 +1,81 @@
+use bevy::{prelude::*, time::Stopwatch};
+use bevy_ecs_ldtk::GridCoords;
+
+use crate::{
+    grid::{Grid, Tile},
+    ldtk::{animation::new_animation, entities::Aim},
+    pathfinding::Path,
+    tuning::GameTuning,
+};
+
+use super::{MovementSpeed, VILLAGER_ANIMATION_IDLE, VILLAGER_ANIMATION_WALK};
+
+/// Going to a [`PointOfInterest`](crate::ldtk::entities::PointOfInterest) and spending some time there.
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Visit {
+    pub target: GridCoords,
+    /// Direction to look at once there.
+    pub facing: Option<Vec2>,
+    /// Time spent there, once reached.
+    pub elapsed: Option<Stopwatch>,
+}
+
+/// When [`Visit`] is added, generate a [`Path`] to the target, or next to it if it can't be reached.
+pub fn visit_on_enter(
+    mut commands: Commands,
+    query: Query<(Entity, &GridCoords, &Visit), Added<Visit>>,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, coords, visit) in &query {
+        let path = grid.path_to(coords, &visit.target).or_else(|_| {
+            // Points of interest like a well are usually obstacles.
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(|(x, y)| GridCoords::new(visit.target.x + x, visit.target.y + y))
+                .find_map(|next_to| grid.path_to(coords, &next_to).ok())
+                .ok_or(())
+        });
+
+        if let Ok(path) = path {
+            commands.entity(entity).insert(path);
+            commands
+                .entity(entity)
+                .insert(MovementSpeed(tuning.normal_speed))
+                .insert(new_animation(VILLAGER_ANIMATION_WALK));
+        }
+    }
+}
+
+/// Once arrived, face the point of interest and stay there.
+pub fn visit_update(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Visit, &mut Aim), Without<Path>>,
+    time: Res<Time>,
+) {
+    for (entity, mut visit, mut aim) in &mut query {
+        if let Some(elapsed) = visit.elapsed.as_mut() {
+            elapsed.tick(time.delta());
+            continue;
+        }
+
+        if let Some(facing) = visit.facing {
+            aim.0 = facing;
+        }
+        commands
+            .entity(entity)
+            .insert(new_animation(VILLAGER_ANIMATION_IDLE));
+
+        visit.elapsed = Some(Stopwatch::new());
+    }
+}
+
+/// When [`Visit`] is removed, remove any [`Path`] and [`MovementSpeed`].
+pub fn visit_on_exit(mut commands: Commands, mut query: RemovedComponents<Visit>) {
+    for entity in query.read() {
+        commands.entity(entity).remove::<Path>();
+        commands.entity(entity).remove::<MovementSpeed>();
+    }
+}
@@ -3,10 +3,10 @@ use bevy_ecs_ldtk::GridCoords;
 use bevy_rand::prelude::{GlobalEntropy, WyRand};
 
 use crate::{
-    config::{NORMAL_SPEED, WANDERING_RADIUS},
     grid::{Grid, Tile},
     ldtk::{animation::new_animation, entities::EnemyTag},
     pathfinding::Path,
+    tuning::GameTuning,
 };
 
 use super::{MovementSpeed, INVESTIGATOR_ANIMATION_WALK, VILLAGER_ANIMATION_WALK};
@@ -22,12 +22,15 @@ pub fn wander_on_enter(
     query: Query<(Entity, &GridCoords, &EnemyTag), Added<Wander>>,
     grid: Res<Grid<Tile>>,
     mut rng: ResMut<GlobalEntropy<WyRand>>,
+    tuning: Res<GameTuning>,
 ) {
     for (entity, coords, tag) in &query {
-        if let Ok(target) = grid.find_nearby(&coords, WANDERING_RADIUS, rng.as_mut()) {
+        if let Ok(target) = grid.find_nearby(&coords, tuning.wandering_radius, rng.as_mut()) {
             if let Ok(path) = grid.path_to(&coords, &target) {
                 commands.entity(entity).insert(path);
-                commands.entity(entity).insert(MovementSpeed(NORMAL_SPEED));
+                commands
+                    .entity(entity)
+                    .insert(MovementSpeed(tuning.normal_speed));
 
                 commands.entity(entity).insert(new_animation(match tag {
                     EnemyTag::Investigator => INVESTIGATOR_ANIMATION_WALK,
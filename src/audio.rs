@@ -1,4 +1,5 @@
 use bevy::{audio::Volume, prelude::*};
+use serde::{Deserialize, Serialize};
 
 use crate::states::GameState;
 
@@ -10,7 +11,7 @@ struct GameMusic;
 #[reflect(Component)]
 struct FadeIn;
 
-#[derive(Default, Reflect)]
+#[derive(Default, Reflect, Serialize, Deserialize, Clone, Copy)]
 pub enum AudioLevels {
     Mute,
     Low,
@@ -0,0 +1,255 @@
+// Campaign
+// Ordered list of levels (in the same order as in the LDtk project), played one after the other.
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_ecs_ldtk::{assets::LdtkProject, prelude::RawLevelAccessor, LevelSelection};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_mode::Score,
+    ldtk::LdtkProjectHandle,
+    states::{GameState, PlayingState},
+    tuning::GameTuning,
+};
+
+#[derive(Reflect, Clone, Default, Debug)]
+pub struct CampaignStats {
+    pub levels_completed: usize,
+    pub villagers_killed: usize,
+}
+
+/// Best result on a level, won or lost.
+#[derive(Reflect, Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct LevelRecord {
+    pub completed: bool,
+    pub best_villagers_killed: usize,
+    pub total_villagers: usize,
+    pub best_time: Option<f32>, // In seconds
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct Campaign {
+    pub levels: Vec<String>,
+    pub current: usize,
+    pub stats: CampaignStats,
+    pub records: HashMap<String, LevelRecord>,
+    pub lifetime_villagers_killed: usize,
+    starting_lives: usize,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self {
+            levels: Vec::new(),
+            current: 0,
+            stats: CampaignStats::default(),
+            records: HashMap::new(),
+            lifetime_villagers_killed: 0,
+            starting_lives: 1,
+        }
+    }
+}
+
+impl Campaign {
+    /// Lives the player starts the current level with.
+    pub fn starting_lives(&self) -> usize {
+        self.starting_lives
+    }
+
+    /// The first level is always unlocked, the others once the previous one is completed.
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index == 0
+            || self
+                .levels
+                .get(index - 1)
+                .and_then(|previous| self.records.get(previous))
+                .is_some_and(|record| record.completed)
+    }
+
+    pub fn record(&self, index: usize) -> Option<&LevelRecord> {
+        self.levels
+            .get(index)
+            .and_then(|identifier| self.records.get(identifier))
+    }
+
+    /// Start the campaign from the given level, with nothing carried over.
+    pub fn select(&mut self, index: usize) {
+        self.current = index;
+        self.stats = CampaignStats::default();
+        self.starting_lives = 1;
+    }
+
+    pub fn has_next_level(&self) -> bool {
+        self.current + 1 < self.levels.len()
+    }
+
+    pub fn level_selection(&self) -> LevelSelection {
+        match self.levels.get(self.current) {
+            Some(identifier) => LevelSelection::Identifier(identifier.clone()),
+            None => LevelSelection::index(self.current),
+        }
+    }
+
+    /// Keep the best [`Score`] of the current level.
+    pub fn update_record(&mut self, score: &Score, completed: bool) {
+        self.lifetime_villagers_killed += score.villagers_killed();
+
+        let Some(identifier) = self.levels.get(self.current) else {
+            return;
+        };
+
+        let record = self.records.entry(identifier.clone()).or_default();
+        record.completed |= completed;
+        record.best_villagers_killed = record.best_villagers_killed.max(score.villagers_killed());
+        record.total_villagers = score.total_villagers();
+
+        if completed {
+            record.best_time = Some(
+                record
+                    .best_time
+                    .map_or(score.time(), |best| best.min(score.time())),
+            );
+        }
+    }
+
+    /// Record the [`Score`] of the level that was just won, and move on to the next one.
+    /// Without carry over, every level starts fresh.
+    pub fn level_completed(&mut self, score: &Score, carry_over: bool) {
+        if carry_over {
+            self.stats.levels_completed += 1;
+            self.stats.villagers_killed += score.villagers_killed();
+            self.starting_lives = score.player_lives().max(1);
+        } else {
+            self.stats = CampaignStats::default();
+            self.starting_lives = 1;
+        }
+
+        if self.has_next_level() {
+            self.current += 1;
+        }
+    }
+}
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Campaign>()
+            .register_type::<Campaign>()
+            .add_systems(OnEnter(GameState::MainMenu), update_levels)
+            .add_systems(OnExit(PlayingState::Loading), update_levels)
+            .add_systems(OnEnter(PlayingState::Win), level_won)
+            .add_systems(OnEnter(PlayingState::Lose), level_lost)
+            .add_systems(OnEnter(GameState::NextLevel), next_level);
+    }
+}
+
+/// Once the LDtk project is loaded, list its levels.
+pub fn update_levels(
+    mut campaign: ResMut<Campaign>,
+    projects: Res<Assets<LdtkProject>>,
+    handle: Option<Res<LdtkProjectHandle>>,
+) {
+    let Some(project) = handle.and_then(|handle| projects.get(&handle.0)) else {
+        return;
+    };
+
+    campaign.levels = project
+        .iter_raw_levels()
+        .map(|level| level.identifier.clone())
+        .collect();
+}
+
+fn level_won(mut campaign: ResMut<Campaign>, score: Res<Score>) {
+    campaign.update_record(&score, true);
+}
+
+fn level_lost(mut campaign: ResMut<Campaign>, score: Res<Score>) {
+    campaign.update_record(&score, false);
+}
+
+/// Go to the next level, this goes through [`GameState::Reset`] to clean up the current one.
+fn next_level(
+    mut campaign: ResMut<Campaign>,
+    score: Res<Score>,
+    tuning: Res<GameTuning>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    campaign.level_completed(&score, tuning.campaign_carry_over);
+
+    *level_selection = campaign.level_selection();
+
+    next_state.set(GameState::Reset);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campaign() -> Campaign {
+        Campaign {
+            levels: vec!["Level_0".to_string(), "Level_1".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn score(killed: usize, lives: usize) -> Score {
+        let mut score = Score::with_lives(lives);
+        for _ in 0..killed {
+            score.villager_spawned();
+            score.villager_killed();
+        }
+        score
+    }
+
+    #[test]
+    fn completing_a_level_moves_to_the_next_one() {
+        let mut campaign = campaign();
+        assert!(campaign.has_next_level());
+
+        campaign.level_completed(&score(2, 3), true);
+
+        assert_eq!(campaign.current, 1);
+        assert!(!campaign.has_next_level());
+        assert_eq!(
+            campaign.level_selection(),
+            LevelSelection::Identifier("Level_1".to_string())
+        );
+    }
+
+    #[test]
+    fn carry_over_keeps_stats_and_lives() {
+        let mut campaign = campaign();
+
+        campaign.level_completed(&score(2, 3), true);
+
+        assert_eq!(campaign.stats.levels_completed, 1);
+        assert_eq!(campaign.stats.villagers_killed, 2);
+        assert_eq!(campaign.starting_lives(), 3);
+    }
+
+    #[test]
+    fn without_carry_over_every_level_starts_fresh() {
+        let mut campaign = campaign();
+
+        campaign.level_completed(&score(2, 3), false);
+
+        assert_eq!(campaign.current, 1);
+        assert_eq!(campaign.stats.levels_completed, 0);
+        assert_eq!(campaign.stats.villagers_killed, 0);
+        assert_eq!(campaign.starting_lives(), 1);
+    }
+
+    #[test]
+    fn last_level_stays_selected() {
+        let mut campaign = campaign();
+        campaign.select(1);
+
+        campaign.level_completed(&score(0, 1), true);
+
+        assert_eq!(campaign.current, 1);
+    }
+}
@@ -7,6 +7,7 @@ use crate::ldtk::animation::{AnimationConfig, ANIMATIONS};
 pub const PIXEL_PER_TILE: f32 = 16.;
 
 /// AI
+/// Most of these are only the defaults of [`crate::tuning::GameTuning`], see `assets/game.tuning.ron`.
 
 pub const INVESTIGATOR_VIEW_RANGE: f32 = 5. * PIXEL_PER_TILE; // In world units
 pub const INVESTIGATOR_VIEW_HALF_ANGLE: f32 = 90.; // In degrees
@@ -22,6 +23,25 @@ pub const CHASE_SPEED: f32 = 6. * PIXEL_PER_TILE; // In world unites per seconds
 pub const INVESTIGATING_RADIUS: u32 = 10; // In seconds
 pub const INVESTIGATING_TIME: u64 = 10; // In seconds
 
+pub const SEARCH_SIGHT_RADIUS: u32 = 2; // In grid units, tiles around an investigator counted as searched
+pub const SEARCH_MEMORY_TIME: f32 = 30.; // In seconds, before a searched tile is worth searching again
+pub const SEARCH_CLAIM_RADIUS: u32 = 4; // In grid units, around the tile another investigator is searching
+pub const SEARCH_HIDDING_SPOT_PRIORITY: f32 = 4.; // Compared to 1 for an open tile
+pub const SEARCH_CORNER_PRIORITY: f32 = 2.; // Compared to 1 for an open tile
+
+pub const INSPECT_CHANCE: f32 = 0.5; // Of an investigator inspecting a hidding spot it searches
+pub const INSPECT_TIME: f32 = 1.5; // In seconds
+pub const INSPECT_CATCH_SUSPICION: f32 = 0.3; // The player is caught at this suspicion, even if not seen entering
+pub const NOISY_HIDDING_SPOT_LOUDNESS: f32 = 0.6; // Fraction of the hearing range
+
+pub const RADIO_RANGE: f32 = 16. * PIXEL_PER_TILE; // In world units
+pub const FLANK_DISTANCE: u32 = 4; // In grid units, ahead of the player
+pub const FLANK_SPREAD: u32 = 3; // In grid units, on each side of the player's way
+
+pub const CHASE_LEAD_TIME: f32 = 0.5; // In seconds, of player movement to aim ahead of while chasing
+pub const CHASE_PREDICTION_TIME: f32 = 1.5; // In seconds, of player movement to extrapolate once out of sight
+pub const CHASE_LEAD_MIN_DISTANCE: u32 = 2; // In grid units, closer than that go straight for the player
+
 pub const WANDERING_RADIUS: u32 = 32; // In grid units
 
 pub const MIN_RUN_AWAY_RADIUS: u32 = 8; // In grid units
@@ -30,11 +50,40 @@ pub const MAX_RUN_AWAY_ANGLE: f32 = 45.; // In degrees
 
 pub const IDLING_TIME: u64 = 5; // In seconds
 
+pub const DAY_LENGTH: f32 = 240.; // In seconds, for 24 in-game hours
+pub const DAY_START_HOUR: f32 = 8.; // In-game hour when a level starts
+pub const ACTIVITY_TIME: f32 = 20.; // In seconds, spent at a point of interest
+pub const VILLAGER_SCHEDULE: [(f32, &str); 3] = [(6., "well"), (10., "market"), (18., "house")]; // From hour, point of interest tag
+
+pub const FOOTSTEP_LOUDNESS: f32 = 0.4; // Fraction of the hearing range, at full speed
+pub const KILL_LOUDNESS: f32 = 0.8; // Fraction of the hearing range
+pub const SCREAM_LOUDNESS: f32 = 1.; // Fraction of the hearing range
+pub const CORPSE_ALARM_LOUDNESS: f32 = 1.5; // Fraction of the hearing range
+pub const MAX_OCCLUDING_WALLS: u32 = 1; // Noises behind more walls are not heard
+
+pub const SUSPICION_BUILD_RATE: f32 = 2.; // Per seconds, when the player is close and in the center of the view
+pub const SUSPICION_DECAY_RATE: f32 = 0.25; // Per seconds, when the player is out of sight
+pub const SUSPICION_INVESTIGATE_THRESHOLD: f32 = 0.5; // Full suspicion (1) is a chase
+
+pub const AMBIENT_LIGHT: f32 = 0.3; // Light level away from any light source, from 0 to 1
+pub const DARK_VIEW_FACTOR: f32 = 0.5; // View range multiplier when the player is in the dark
+
+pub const INVESTIGATOR_LANTERN_RANGE: f32 = 4. * PIXEL_PER_TILE; // In world units
+pub const VILLAGER_LANTERN_RANGE: f32 = 3. * PIXEL_PER_TILE; // In world units
+pub const LANTERN_HALF_ANGLE: f32 = 30.; // In degrees, matches the light sprites
+
+pub const FOOTSTEP_INTERVAL: f32 = 0.4; // In seconds
+pub const SCREAM_INTERVAL: f32 = 1.5; // In seconds
+pub const RADIO_INTERVAL: f32 = 2.; // In seconds, between calls while chasing
+
 pub const FIND_NEARBY_MAX_TRIES: u32 = 10;
 
+pub const PATHFINDING_BUDGET: u32 = 8; // Paths planned per frame, the others wait for the next frames
+
 // AI & PLAYER
 
 pub const PLAYER_SPEED: f32 = 7. * PIXEL_PER_TILE; // In world unites per seconds
+pub const DRAG_SPEED_FACTOR: f32 = 0.5; // Player speed multiplier when dragging a corpse
 
 pub const INTERACTION_DISTANCE: f32 = 1.1 * PIXEL_PER_TILE; // In world units
 
@@ -43,6 +92,21 @@ pub const INTERACTION_DISTANCE: f32 = 1.1 * PIXEL_PER_TILE; // In world units
 pub const GRID_SIZE: IVec2 = IVec2::new(54, 40); // Defined in the ldtk file
 pub const TILE_SIZE: IVec2 = IVec2::splat(PIXEL_PER_TILE as i32); // Defined in the ldtk file
 
+// CAMPAIGN
+
+pub const CAMPAIGN_CARRY_OVER: bool = true; // Lives and stats carry over to the next level
+
+// INPUT
+
+pub const STICK_INNER_DEADZONE: f32 = 0.1; // Below is no movement, to filter drift
+pub const STICK_OUTER_DEADZONE: f32 = 0.95; // Above is full speed, sticks rarely reach 1
+pub const STICK_RESPONSE_EXPONENT: f32 = 1.5; // Above 1 gives finer control at low speed
+pub const STICK_MENU_THRESHOLD: f32 = 0.5; // Shaped stick value to navigate menus
+
+// SIMULATION
+
+pub const SIM_TIMESTEP: f32 = 1. / 60.; // In seconds
+
 // ANIMATIONS
 
 //// PLAYER
@@ -64,6 +128,9 @@ pub const INVESTIGATOR_ANIMATION_WALK: AnimationConfig =
     AnimationConfig::new(ANIMATIONS::InvestigatorWalk, 16, 19, 8).repeats();
 pub const INVESTIGATOR_ANIMATION_RUN: AnimationConfig =
     AnimationConfig::new(ANIMATIONS::InvestigatorRun, 32, 35, 8).repeats();
+// The sheet has no inspect sprites, so shuffle in place between two walk frames.
+pub const INVESTIGATOR_ANIMATION_INSPECT: AnimationConfig =
+    AnimationConfig::new(ANIMATIONS::InvestigatorInspect, 17, 18, 3).repeats();
 
 //// VILLAGER
 pub const VILLAGER_ANIMATION_IDLE: AnimationConfig =
@@ -0,0 +1,71 @@
+// Debug overlay
+// Dev only, draws what the AI is thinking on top of the game. Toggled with F3.
+// Needs the `debug_overlay` feature, for Bevy's gizmos.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::utils::grid_coords_to_translation;
+
+use crate::{
+    ai::{Chase, Investigate},
+    config::TILE_SIZE,
+    ldtk::entities::player::{PlayerTag, PlayerVelocity},
+    states::PlayingState,
+};
+
+const PREDICTION_COLOR: Color = Color::srgb(1., 0.3, 0.2);
+const INVESTIGATE_COLOR: Color = Color::srgb(1., 0.8, 0.2);
+const VELOCITY_COLOR: Color = Color::srgb(0.3, 0.8, 1.);
+
+#[derive(Resource, Default)]
+struct DebugOverlay {
+    enabled: bool,
+}
+
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugOverlay>().add_systems(
+            Update,
+            (
+                toggle_overlay,
+                draw_predictions
+                    .run_if(in_state(PlayingState::Playing))
+                    .run_if(|overlay: Res<DebugOverlay>| overlay.enabled),
+            ),
+        );
+    }
+}
+
+fn toggle_overlay(keyboard: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<DebugOverlay>) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+/// Where chasers aim ahead of the player, and where investigators think the player went.
+fn draw_predictions(
+    mut gizmos: Gizmos,
+    player: Query<(&Transform, &PlayerVelocity), With<PlayerTag>>,
+    chasers: Query<(&Transform, &Chase)>,
+    investigators: Query<(&Transform, &Investigate)>,
+) {
+    let half_tile = TILE_SIZE.x as f32 / 2.;
+
+    if let Ok((transform, velocity)) = player.get_single() {
+        let location = transform.translation.xy();
+        gizmos.arrow_2d(location, location + velocity.0 * 0.5, VELOCITY_COLOR);
+    }
+
+    for (transform, chase) in &chasers {
+        let predicted = grid_coords_to_translation(chase.predicted, TILE_SIZE);
+        gizmos.line_2d(transform.translation.xy(), predicted, PREDICTION_COLOR);
+        gizmos.circle_2d(predicted, half_tile, PREDICTION_COLOR);
+    }
+
+    for (transform, investigate) in &investigators {
+        let target = grid_coords_to_translation(investigate.target, TILE_SIZE);
+        gizmos.line_2d(transform.translation.xy(), target, INVESTIGATE_COLOR);
+        gizmos.rect_2d(target, 0., Vec2::splat(half_tile * 2.), INVESTIGATE_COLOR);
+    }
+}
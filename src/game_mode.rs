@@ -3,6 +3,7 @@ use bevy::prelude::*;
 
 use crate::{
     ai::Chased,
+    campaign::Campaign,
     config::{PLAYER_ANIMATION_DEATH, PLAYER_ANIMATION_IDLE},
     grid::{Grid, Tile},
     ldtk::{
@@ -19,12 +20,13 @@ use crate::{
     states::{GameState, PlayingState},
 };
 
-#[derive(Resource, Reflect)]
+#[derive(Resource, Reflect, Debug)]
 #[reflect(Resource)]
 pub struct Score {
     total_villagers: usize,
     villagers_killed: usize,
     player_lives: usize,
+    time: f32, // In seconds
 }
 
 impl Default for Score {
@@ -33,11 +35,45 @@ impl Default for Score {
             total_villagers: 0,
             villagers_killed: 0,
             player_lives: 1,
+            time: 0.,
         }
     }
 }
 
 impl Score {
+    pub fn with_lives(player_lives: usize) -> Self {
+        Self {
+            player_lives,
+            ..Default::default()
+        }
+    }
+
+    pub fn total_villagers(&self) -> usize {
+        self.total_villagers
+    }
+
+    pub fn villagers_killed(&self) -> usize {
+        self.villagers_killed
+    }
+
+    pub fn player_lives(&self) -> usize {
+        self.player_lives
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Put back the lives and time of a suspended level.
+    pub fn restore(&mut self, player_lives: usize, time: f32) {
+        self.player_lives = player_lives;
+        self.time = time;
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.time += delta;
+    }
+
     pub fn villager_spawned(&mut self) {
         self.total_villagers += 1;
     }
@@ -51,6 +87,12 @@ impl Score {
     }
 }
 
+/// Where the player started the level.
+/// Used to respawn when there are lives left (carried over from a previous level), but no [`PlayerRespawnPointTag`].
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct PlayerStartPoint(pub Transform);
+
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
@@ -68,7 +110,8 @@ impl Plugin for GamePlugin {
             )
             .add_systems(
                 Update,
-                (check_win_condition, update_ui).run_if(in_state(PlayingState::Playing)),
+                (check_win_condition, update_ui, update_time, store_player_start)
+                    .run_if(in_state(PlayingState::Playing)),
             )
             .add_systems(Update, player_died.run_if(in_state(PlayingState::Death)))
             // .add_systems(Update, log_transitions::<PlayingState>);
@@ -150,6 +193,17 @@ fn update_ui(
     }
 }
 
+fn update_time(mut score: ResMut<Score>, time: Res<Time>) {
+    // Don't trigger change detection, otherwise the UI would be updated every frame.
+    score.bypass_change_detection().tick(time.delta_seconds());
+}
+
+fn store_player_start(mut commands: Commands, player: Query<&Transform, Added<PlayerTag>>) {
+    if let Ok(transform) = player.get_single() {
+        commands.insert_resource(PlayerStartPoint(*transform));
+    }
+}
+
 fn player_death(
     mut commands: Commands,
     mut player: Query<Entity, With<PlayerTag>>,
@@ -207,11 +261,18 @@ fn player_respawn(
             (Without<PlayerTag>, Without<Cameras>),
         ),
     >,
+    start_point: Option<Res<PlayerStartPoint>>,
     dead_player_handle: Res<DeadPlayerSpriteHandle>,
     mut next_state: ResMut<NextState<PlayingState>>,
 ) {
+    // Use the last respawn point, or the start of the level if none are left.
+    let respawn_point = respawn_points.iter().last();
+
     // Just in case we can't find what we need, trigger lose condition.
-    let Some((respawn_entity, respawn_transform)) = respawn_points.iter().last() else {
+    let Some(respawn_transform) = respawn_point
+        .map(|(_, transform)| *transform)
+        .or(start_point.map(|start| start.0))
+    else {
         next_state.set(PlayingState::Lose);
         warn!("Could not get respawn points");
         return;
@@ -223,11 +284,8 @@ fn player_respawn(
         return;
     };
 
-    let Ok((camera, mut camera_transfrom)) = camera.get_single_mut() else {
-        next_state.set(PlayingState::Lose);
-        warn!("Could not get camera group");
-        return;
-    };
+    // There is no camera group when running headless.
+    let mut camera = camera.get_single_mut().ok();
 
     // Spawn dead "player", so it's visible later
     commands.spawn(DeadPlayerBundle::new(
@@ -239,17 +297,17 @@ fn player_respawn(
     // Hide player
     *visibility = Visibility::Hidden;
 
-    // Detach camera
-    commands.entity(player).remove_children(&[camera]);
-
-    // Reset camera transforms
-    *camera_transfrom = Transform::IDENTITY;
+    // Detach camera and reset its transforms
+    if let Some((camera, camera_transfrom)) = &mut camera {
+        commands.entity(player).remove_children(&[*camera]);
+        **camera_transfrom = Transform::IDENTITY;
+    }
 
     // Remove chased tag
     commands.entity(player).remove::<Chased>();
 
     // Move player to respawn point
-    *player_transform = *respawn_transform;
+    *player_transform = respawn_transform;
 
     // Player Idle animation
     commands
@@ -257,13 +315,17 @@ fn player_respawn(
         .insert(new_animation_during_death(PLAYER_ANIMATION_IDLE));
 
     // Despawn respawn point
-    commands.entity(respawn_entity).despawn_recursive();
+    if let Some((respawn_entity, _)) = respawn_point {
+        commands.entity(respawn_entity).despawn_recursive();
+    }
 
     // Show player
     *visibility = Visibility::Inherited;
 
-    // Attach camera
-    commands.entity(player).add_child(camera);
+    // Attach camera back
+    if let Some((camera, _)) = camera {
+        commands.entity(player).add_child(camera);
+    }
 
     // Switch to playing state
     next_state.set(PlayingState::Playing);
@@ -274,11 +336,12 @@ fn reset(
     mut cameras: Query<&mut Transform, With<Cameras>>,
     dead_players: Query<Entity, With<DeadPlayerTag>>,
     mut grid: ResMut<Grid<Tile>>,
-    mut score: ResMut<Score>,
+    campaign: Res<Campaign>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    // Reset score
-    *score = Score::default();
+    // Reset score, keeping any lives carried over from the previous level.
+    // Inserted, as there is no score yet when coming from the main menu.
+    commands.insert_resource(Score::with_lives(campaign.starting_lives()));
 
     // Remove any rotations on cameras group.
     for mut transform in &mut cameras {
@@ -69,6 +69,28 @@ impl<T> Grid<T> {
         Grid::<T>::valid_index(location) && self[location].is_some()
     }
 
+    /// Number of separate runs of occupied tiles crossed by the segment, in world units.
+    pub fn walls_between(&self, from: Vec2, to: Vec2) -> u32 {
+        // Sample every quarter tile, so thin walls aren't skipped.
+        let steps = (from.distance(to) / (TILE_SIZE.x as f32 / 4.)).ceil() as u32;
+
+        let mut walls = 0;
+        let mut in_wall = false;
+
+        for step in 0..=steps {
+            let position = from.lerp(to, step as f32 / steps.max(1) as f32);
+            let occupied =
+                GridLocation::from_world(position).is_some_and(|location| self.occupied(&location));
+
+            if occupied && !in_wall {
+                walls += 1;
+            }
+            in_wall = occupied;
+        }
+
+        walls
+    }
+
     pub fn valid_index(location: &GridLocation) -> bool {
         location.x >= 0 && location.y >= 0 && location.x < GRID_SIZE.x && location.y < GRID_SIZE.y
     }
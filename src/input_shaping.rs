@@ -0,0 +1,123 @@
+// Input shaping
+// Turns raw analog stick values into a direction: radial deadzone (so drift is filtered the same in every direction),
+// outer deadzone (sticks rarely reach 1), response curve, and clamped to the unit circle so diagonals aren't faster.
+
+use bevy::prelude::*;
+
+use crate::config::{STICK_INNER_DEADZONE, STICK_OUTER_DEADZONE, STICK_RESPONSE_EXPONENT};
+
+#[derive(Resource, Reflect, Clone, Copy, Debug)]
+#[reflect(Resource)]
+pub struct StickShaping {
+    pub inner_deadzone: f32,
+    pub outer_deadzone: f32,
+    pub response_exponent: f32,
+}
+
+impl Default for StickShaping {
+    fn default() -> Self {
+        Self {
+            inner_deadzone: STICK_INNER_DEADZONE,
+            outer_deadzone: STICK_OUTER_DEADZONE,
+            response_exponent: STICK_RESPONSE_EXPONENT,
+        }
+    }
+}
+
+impl StickShaping {
+    /// Shape a raw stick position, the result has a length between 0 and 1, in the same direction.
+    pub fn shape(&self, raw: Vec2) -> Vec2 {
+        let length = raw.length();
+
+        if length <= self.inner_deadzone || !length.is_finite() {
+            return Vec2::ZERO;
+        }
+
+        // Remap the live zone between both deadzones to 0..1.
+        let live_zone = (self.outer_deadzone - self.inner_deadzone).max(f32::EPSILON);
+        let magnitude = ((length - self.inner_deadzone) / live_zone).clamp(0., 1.);
+
+        raw / length * magnitude.powf(self.response_exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn shaping() -> StickShaping {
+        StickShaping {
+            inner_deadzone: 0.2,
+            outer_deadzone: 0.9,
+            response_exponent: 2.,
+        }
+    }
+
+    #[test]
+    fn inside_inner_deadzone_is_zero() {
+        let shaping = shaping();
+
+        for raw in [
+            Vec2::ZERO,
+            Vec2::new(0.1, 0.),
+            Vec2::new(-0.1, 0.1),
+            Vec2::Y * 0.2,
+        ] {
+            assert_eq!(shaping.shape(raw), Vec2::ZERO, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn past_outer_deadzone_is_unit_length() {
+        let shaping = shaping();
+
+        for raw in [Vec2::X * 0.9, Vec2::new(0., -0.95), Vec2::new(1., 0.)] {
+            assert!(
+                (shaping.shape(raw).length() - 1.).abs() < EPSILON,
+                "{:?}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_direction_in_every_quadrant() {
+        let shaping = shaping();
+
+        for raw in [
+            Vec2::new(0.5, 0.3),
+            Vec2::new(-0.5, 0.3),
+            Vec2::new(-0.5, -0.3),
+            Vec2::new(0.5, -0.3),
+        ] {
+            let shaped = shaping.shape(raw);
+            assert_eq!(shaped.x.signum(), raw.x.signum(), "{:?}", raw);
+            assert_eq!(shaped.y.signum(), raw.y.signum(), "{:?}", raw);
+            assert!(shaped.normalize().abs_diff_eq(raw.normalize(), EPSILON));
+        }
+    }
+
+    #[test]
+    fn diagonals_are_clamped_to_unit_circle() {
+        let shaping = shaping();
+
+        for raw in [
+            Vec2::ONE,
+            -Vec2::ONE,
+            Vec2::new(1., -1.),
+            Vec2::new(-1., 1.),
+        ] {
+            assert!(shaping.shape(raw).length() <= 1. + EPSILON, "{:?}", raw);
+        }
+    }
+
+    #[test]
+    fn continuous_at_inner_deadzone_edge() {
+        let shaping = shaping();
+        let edge = Vec2::X * (shaping.inner_deadzone + EPSILON);
+
+        assert!(shaping.shape(edge).length() < 1e-3);
+    }
+}
@@ -13,6 +13,7 @@ pub enum ANIMATIONS {
     InvestigatorIdle,
     InvestigatorWalk,
     InvestigatorRun,
+    InvestigatorInspect,
     VillagerIdle,
     VillagerWalk,
     VillagerFlee,
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{ColliderBundle, InteractibleEntityRef, InteractibleTag};
+
+/// Dead villager stashed in a hidding spot, enemies can't find it anymore.
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+pub struct CorpseHidden;
+
+/// Let the player drag a corpse around.
+pub fn add_corpse_sensor(commands: &mut Commands, corpse: Entity) {
+    let sensor = commands
+        .spawn((
+            TransformBundle::default(),
+            InteractibleTag::Corpse,
+            ColliderBundle {
+                collider: Collider::cuboid(8. * 1.5, 8. * 1.5),
+                ..Default::default()
+            },
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::STATIC_STATIC,
+            InteractibleEntityRef(corpse),
+        ))
+        .id();
+
+    commands.entity(corpse).add_child(sensor);
+}
+
+pub fn corpse_hidden_added(mut query: Query<&mut Visibility, Added<CorpseHidden>>) {
+    for mut visibility in &mut query {
+        *visibility = Visibility::Hidden;
+    }
+}
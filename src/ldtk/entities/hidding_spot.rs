@@ -11,11 +11,30 @@ use super::TILE_SIZE;
 #[reflect(Component)]
 pub struct HiddingSpotExit(pub Vec2);
 
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+pub struct HiddingSpot {
+    /// Bodies it can hold, the player included. Unlimited when not set.
+    pub capacity: Option<u32>,
+    /// Entering or leaving it makes noise.
+    pub noisy: bool,
+}
+
+impl HiddingSpot {
+    /// No one else fits once `occupants` bodies are in.
+    pub fn is_full(&self, occupants: usize) -> bool {
+        self.capacity
+            .is_some_and(|capacity| occupants >= capacity as usize)
+    }
+}
+
 #[derive(Bundle, LdtkEntity)]
 pub struct HiddingSpotBundle {
     render_layer: RenderLayers,
     #[with(exit_from_field)]
     exit: HiddingSpotExit,
+    #[with(hidding_spot_from_fields)]
+    hidding_spot: HiddingSpot,
 }
 
 impl Default for HiddingSpotBundle {
@@ -23,6 +42,7 @@ impl Default for HiddingSpotBundle {
         HiddingSpotBundle {
             render_layer: PIXEL_PERFECT_LAYERS,
             exit: HiddingSpotExit::default(),
+            hidding_spot: HiddingSpot::default(),
         }
     }
 }
@@ -38,3 +58,52 @@ fn exit_from_field(entity_instance: &EntityInstance) -> HiddingSpotExit {
         TILE_SIZE,
     ))
 }
+
+fn hidding_spot_from_fields(entity_instance: &EntityInstance) -> HiddingSpot {
+    HiddingSpot {
+        capacity: entity_instance
+            .get_maybe_int_field("capacity")
+            .ok()
+            .copied()
+            .flatten()
+            .map(|capacity| capacity.max(1) as u32),
+        noisy: entity_instance
+            .get_bool_field("noisy")
+            .copied()
+            .unwrap_or(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hidding_spot(capacity: Option<u32>) -> HiddingSpot {
+        HiddingSpot {
+            capacity,
+            noisy: false,
+        }
+    }
+
+    #[test]
+    fn capacity_one_is_full_with_one_occupant() {
+        let spot = hidding_spot(Some(1));
+
+        assert!(!spot.is_full(0));
+        assert!(spot.is_full(1));
+    }
+
+    #[test]
+    fn capacity_two_is_full_with_two_occupants() {
+        let spot = hidding_spot(Some(2));
+
+        assert!(!spot.is_full(0));
+        assert!(!spot.is_full(1));
+        assert!(spot.is_full(2));
+    }
+
+    #[test]
+    fn unlimited_is_never_full() {
+        assert!(!hidding_spot(None).is_full(100));
+    }
+}
@@ -10,6 +10,7 @@ pub enum InteractibleTag {
     HiddingSpot,
     NoiseMaker,
     Villager,
+    Corpse,
 }
 
 impl InteractibleTag {
@@ -11,6 +11,8 @@ pub use crate::{
     },
     rendering::PIXEL_PERFECT_LAYERS,
 };
+use bevy_ecs_ldtk::utils::ldtk_grid_coords_to_grid_coords;
+
 use crate::{
     ldtk::{animation::AnimationOffset, EnemyLights, Light},
     rendering::LIGHTS_LAYERS,
@@ -19,6 +21,20 @@ use crate::{
 
 use super::{Aim, EnemyTag};
 
+/// Stop of a [`PatrolRoute`].
+#[derive(Reflect, Clone, Default)]
+pub struct Waypoint {
+    pub coords: GridCoords,
+    pub wait: f32, // In seconds
+    /// Direction to look at while waiting, otherwise keep looking where it walked.
+    pub facing: Option<Vec2>,
+}
+
+/// Waypoints an investigator loops through, authored in LDtk. Wanders around when empty.
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+pub struct PatrolRoute(pub Vec<Waypoint>);
+
 #[derive(Bundle, LdtkEntity)]
 pub struct InvestigatorBundle {
     collider: ColliderBundle,
@@ -33,6 +49,8 @@ pub struct InvestigatorBundle {
     #[grid_coords]
     grid_coords: GridCoords,
     aim: Aim,
+    #[with(patrol_route_from_fields)]
+    patrol_route: PatrolRoute,
 }
 
 impl Default for InvestigatorBundle {
@@ -48,10 +66,41 @@ impl Default for InvestigatorBundle {
             sprite_sheet_bundle: LdtkSpriteSheetBundle::default(),
             grid_coords: GridCoords::default(),
             aim: Aim::default(),
+            patrol_route: PatrolRoute::default(),
         }
     }
 }
 
+fn patrol_route_from_fields(entity_instance: &EntityInstance) -> PatrolRoute {
+    let Ok(points) = entity_instance.get_maybe_points_field("patrol") else {
+        return PatrolRoute::default();
+    };
+    let waits = entity_instance
+        .get_maybe_floats_field("patrol_wait")
+        .unwrap_or_default();
+    let facings = entity_instance
+        .get_maybe_floats_field("patrol_facing")
+        .unwrap_or_default();
+
+    PatrolRoute(
+        points
+            .iter()
+            .enumerate()
+            .filter_map(|(index, point)| {
+                Some(Waypoint {
+                    coords: ldtk_grid_coords_to_grid_coords((*point)?, GRID_SIZE.y),
+                    wait: waits.get(index).copied().flatten().unwrap_or(0.),
+                    facing: facings
+                        .get(index)
+                        .copied()
+                        .flatten()
+                        .map(|angle| Vec2::from_angle(angle.to_radians())),
+                })
+            })
+            .collect(),
+    )
+}
+
 pub fn investigator_added(
     mut commands: Commands,
     query: Query<(Entity, &EnemyTag), Added<EnemyTag>>,
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::config::PIXEL_PER_TILE;
+
+/// Static light source, feeding the [`LightMap`](crate::light_map::LightMap).
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+pub struct Lamp {
+    pub radius: f32, // In world units
+    pub intensity: f32,
+}
+
+impl Default for Lamp {
+    fn default() -> Self {
+        Lamp {
+            radius: 4. * PIXEL_PER_TILE,
+            intensity: 1.,
+        }
+    }
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct LampBundle {
+    transform: TransformBundle,
+    #[with(lamp_from_fields)]
+    lamp: Lamp,
+}
+
+pub fn lamp_from_fields(entity_instance: &EntityInstance) -> Lamp {
+    let default = Lamp::default();
+
+    Lamp {
+        radius: entity_instance
+            .get_float_field("radius")
+            .map(|radius| radius * PIXEL_PER_TILE)
+            .unwrap_or(default.radius),
+        intensity: entity_instance
+            .get_float_field("intensity")
+            .map(|intensity| intensity.clamp(0., 1.))
+            .unwrap_or(default.intensity),
+    }
+}
@@ -1,23 +1,29 @@
 pub mod collision_tile;
+pub mod corpse;
 pub mod dead_player;
 pub mod hidding_spot;
 pub mod interactible;
 pub mod investigator;
+pub mod lamp;
 pub mod noise_maker;
 pub mod player;
 pub mod player_respawn_point;
+pub mod point_of_interest;
 pub mod villager;
 
 use bevy_ecs_ldtk::{prelude::LdtkFields, EntityIid, EntityInstance, LdtkIntCell};
 use bevy_rapier2d::prelude::*;
 
 pub use collision_tile::CollisionTileBundle;
+pub use corpse::*;
 pub use hidding_spot::*;
 pub use interactible::*;
 pub use investigator::*;
+pub use lamp::*;
 pub use noise_maker::*;
 pub use player::*;
 pub use player_respawn_point::*;
+pub use point_of_interest::*;
 pub use villager::*;
 
 // IMPROVEME
@@ -6,11 +6,6 @@ pub use crate::rendering::PIXEL_PERFECT_LAYERS;
 
 use super::{player::PlayerTag, InteractibleEntityRef, InteractionPossible, GRID_SIZE};
 
-#[derive(Reflect, Clone, Component)]
-#[reflect(Component)]
-#[component(storage = "SparseSet")]
-pub struct NoiseMakerTriggered(pub GridCoords);
-
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
 pub struct NoiseMakerTriggerable;
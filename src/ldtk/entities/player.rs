@@ -16,6 +16,11 @@ pub use crate::{
 #[reflect(Component)]
 pub struct PlayerTag;
 
+/// How fast the player is moving, in world units per seconds.
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+pub struct PlayerVelocity(pub Vec2);
+
 #[derive(Bundle, LdtkEntity)]
 pub struct PlayerBundle {
     collider: ColliderBundle,
@@ -24,6 +29,7 @@ pub struct PlayerBundle {
     animation: AnimationConfig,
     animation_timer: AnimationTimer,
     tag: PlayerTag,
+    velocity: PlayerVelocity,
     render_layer: RenderLayers,
     name: Name,
     #[sprite_sheet_bundle]
@@ -44,6 +50,7 @@ impl Default for PlayerBundle {
             animation: PLAYER_ANIMATION_IDLE,
             animation_timer: AnimationTimer::new(PLAYER_ANIMATION_IDLE),
             tag: PlayerTag,
+            velocity: PlayerVelocity::default(),
             render_layer: PIXEL_PERFECT_LAYERS,
             name: Name::new("Player"),
             sprite_sheet_bundle: LdtkSpriteSheetBundle::default(),
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+/// Place villagers go to, when their [`VillagerSchedule`](crate::ai::VillagerSchedule) has an activity matching one of its tags.
+#[derive(Reflect, Clone, Component, Default)]
+#[reflect(Component)]
+pub struct PointOfInterest {
+    pub tags: Vec<String>,
+    /// Direction to look at while there.
+    pub facing: Option<Vec2>,
+}
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct PointOfInterestBundle {
+    transform: TransformBundle,
+    #[grid_coords]
+    grid_coords: GridCoords,
+    #[with(point_of_interest_from_fields)]
+    point_of_interest: PointOfInterest,
+}
+
+fn point_of_interest_from_fields(entity_instance: &EntityInstance) -> PointOfInterest {
+    PointOfInterest {
+        tags: entity_instance
+            .get_maybe_strings_field("tags")
+            .map(|tags| tags.iter().flatten().cloned().collect())
+            .unwrap_or_default(),
+        facing: entity_instance
+            .get_maybe_float_field("facing")
+            .ok()
+            .copied()
+            .flatten()
+            .map(|angle| Vec2::from_angle(angle.to_radians())),
+    }
+}
@@ -4,16 +4,17 @@ use animation::{
 };
 use bevy::{prelude::*, utils::hashbrown::HashMap};
 use bevy_ecs_tilemap::tiles::TileTextureIndex;
-use bevy_rand::prelude::{GlobalEntropy, WyRand};
+use bevy_rand::prelude::WyRand;
 use collision_tile::AICollisionTileBundle;
 use iyes_progress::prelude::*;
-use rand_core::RngCore;
+use rand_core::{RngCore, SeedableRng};
 
 pub mod animation;
 pub mod entities;
 
 use crate::{
-    ai::Chased, player_controller::PlayerIsHidding, rendering::HEIGHT_LAYERS, utils::remap_rand_f32,
+    ai::Chased, player_controller::PlayerIsHidding, rendering::HEIGHT_LAYERS, seed::SeededRng,
+    utils::remap_rand_f32,
 };
 use bevy_ecs_ldtk::{
     app::{LdtkEntityAppExt, LdtkIntCellAppExt},
@@ -23,7 +24,7 @@ use bevy_ecs_ldtk::{
 };
 use bevy_rapier2d::prelude::*;
 use entities::{InteractionPossible, NoiseMakerBundle};
-use hidding_spot::HiddingSpotBundle;
+use hidding_spot::{HiddingSpot, HiddingSpotBundle};
 use player::PlayerTag;
 
 use crate::{
@@ -33,6 +34,12 @@ use crate::{
     states::{GameState, PlayingState},
 };
 
+const LDTK_PROJECT_PATH: &str = "ldtk/spooky_game.ldtk";
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct LdtkProjectHandle(pub Handle<LdtkProject>);
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct SpaceBarSpriteHandle(Handle<Image>);
@@ -61,6 +68,17 @@ pub struct ConstantAnimatedLdtkLayer;
 #[reflect(Resource)]
 pub struct ConstantAnimatedLdtkLayerTimer(pub Timer);
 
+/// Randomness for cosmetic animations.
+/// Kept apart from the seeded [`GlobalEntropy`](bevy_rand::prelude::GlobalEntropy), so animated tiles never shift the gameplay draws.
+#[derive(Resource)]
+struct AnimationRng(WyRand);
+
+impl Default for AnimationRng {
+    fn default() -> Self {
+        Self(WyRand::seed_from_u64(0))
+    }
+}
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct EnemyLights {
@@ -91,6 +109,8 @@ impl Plugin for MyLdtkPlugin {
         .register_ldtk_entity::<NoiseMakerBundle>("NoiseMaker")
         .register_ldtk_entity::<InteractibleBundle>("Interactible")
         .register_ldtk_entity::<PlayerRespawnPointBundle>("PlayerRespawnPoint")
+        .register_ldtk_entity::<LampBundle>("Lamp")
+        .register_ldtk_entity::<PointOfInterestBundle>("PointOfInterest")
         .register_ldtk_int_cell::<CollisionTileBundle>(1)
         .register_ldtk_int_cell::<AICollisionTileBundle>(2)
         .register_type::<InteractionPossible>()
@@ -99,8 +119,15 @@ impl Plugin for MyLdtkPlugin {
         .register_type::<ActiveEvents>()
         .register_type::<EnemyTag>()
         .register_type::<Aim>()
+        .register_type::<PatrolRoute>()
+        .register_type::<Lamp>()
+        .register_type::<PointOfInterest>()
+        .register_type::<HiddingSpot>()
+        .register_type::<CorpseHidden>()
         .register_type::<AnimationConfig>()
+        .init_resource::<AnimationRng>()
         .add_event::<AnimationFinishedEvent>()
+        .add_systems(OnEnter(GameState::Loading), load_project)
         .add_systems(OnEnter(PlayingState::Loading), setup)
         .add_systems(OnExit(GameState::Playing), cleanup)
         .add_systems(
@@ -112,9 +139,11 @@ impl Plugin for MyLdtkPlugin {
                 update_grid_coords,
                 interaction_events,
                 noise_maker_trigger_removed,
-                investigator_added,
-                villager_added,
+                (investigator_added, villager_added)
+                    .chain()
+                    .in_set(SeededRng::Spawn),
                 on_respawn_point_added,
+                corpse_hidden_added,
                 animation_changed,
                 animation_offset_changed,
                 modify_ldtk_layers,
@@ -130,16 +159,35 @@ impl Plugin for MyLdtkPlugin {
                 .run_if(in_state(PlayingState::Death)),
         )
         .insert_resource(LevelSelection::index(0));
+
+        #[cfg(feature = "hot_reload")]
+        app.add_systems(
+            Update,
+            reload_modified_level.run_if(in_state(PlayingState::Playing)),
+        );
     }
 }
 
+/// Load the LDtk project early, so the levels can be listed in the menus.
+fn load_project(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<AssetsLoading>,
+) {
+    let ldtk_file: Handle<LdtkProject> = asset_server.load(LDTK_PROJECT_PATH);
+
+    loading.add(&ldtk_file);
+
+    commands.insert_resource(LdtkProjectHandle(ldtk_file));
+}
+
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
     mut loading: ResMut<AssetsLoading>,
 ) {
-    let ldtk_file: Handle<LdtkProject> = asset_server.load("ldtk/spooky_game.ldtk");
+    let ldtk_file: Handle<LdtkProject> = asset_server.load(LDTK_PROJECT_PATH);
     let spacebar_sprite: Handle<Image> = asset_server.load("2d/space_bar.png");
     let deadplayer_sprite: Handle<Image> = asset_server.load("2d/dead_player.png");
 
@@ -160,6 +208,7 @@ fn setup(
         ..default()
     });
 
+    commands.insert_resource(LdtkProjectHandle(ldtk_file.clone()));
     commands.insert_resource(SpaceBarSpriteHandle(spacebar_sprite.clone()));
     commands.insert_resource(DeadPlayerSpriteHandle(deadplayer_sprite.clone()));
 
@@ -194,6 +243,22 @@ fn setup(
     });
 }
 
+/// When the LDtk file changes on disk, restart the level through [`GameState::Reset`],
+/// so the [`Grid<Tile>`](crate::grid::Grid), the [`Score`](crate::game_mode::Score) and all the entities are rebuilt from scratch.
+/// Other assets (sprites, shaders, tuning) are reloaded in place by Bevy.
+#[cfg(feature = "hot_reload")]
+fn reload_modified_level(
+    mut events: EventReader<AssetEvent<LdtkProject>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for event in events.read() {
+        if let AssetEvent::Modified { .. } = event {
+            info!("LDtk project modified, reloading level");
+            next_state.set(GameState::Reset);
+        }
+    }
+}
+
 fn cleanup(mut commands: Commands, ldtk_world: Query<Entity, With<Handle<LdtkProject>>>) {
     for entity in &ldtk_world {
         commands.entity(entity).despawn_recursive();
@@ -363,7 +428,7 @@ fn update_layer_animations(
     mut query_children: Query<&mut TileTextureIndex, Without<AnimatedLdtkLayer>>,
     mut timer: ResMut<AnimatedLdtkLayerTimer>,
     time: Res<Time>,
-    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    mut rng: ResMut<AnimationRng>,
 ) {
     timer.0.tick(time.delta());
 
@@ -372,7 +437,7 @@ fn update_layer_animations(
             for child in children {
                 if let Ok(mut index) = query_children.get_mut(*child) {
                     // 10% chance to animate the tile
-                    if remap_rand_f32(rng.next_u32(), 0., 1.) < 0.1 {
+                    if remap_rand_f32(rng.0.next_u32(), 0., 1.) < 0.1 {
                         let x = index.0.rem_euclid(4);
                         let y = index.0 / 4;
 
@@ -0,0 +1,160 @@
+// Light map
+// How lit each tile is, computed on the CPU so the AI can use it, even headless.
+// Lamps authored in LDtk are baked when they (or the walls) change, enemy lanterns are added every frame.
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{
+    utils::{grid_coords_to_translation, translation_to_grid_coords},
+    GridCoords,
+};
+
+use crate::{
+    ai::Dead,
+    config::{GRID_SIZE, TILE_SIZE},
+    grid::{Grid, Tile},
+    ldtk::entities::{Aim, EnemyTag, Lamp},
+    states::PlayingState,
+    tuning::GameTuning,
+};
+
+type Levels = [[f32; GRID_SIZE.y as usize]; GRID_SIZE.x as usize];
+
+/// Light level of each tile, from 0 (dark) to 1 (fully lit).
+#[derive(Resource, Clone)]
+pub struct LightMap {
+    /// Ambient and lamps.
+    baked: Levels,
+    /// Baked levels plus enemy lanterns.
+    levels: Levels,
+    /// View range multiplier when the player stands in the dark.
+    dark_view_factor: f32,
+}
+
+impl Default for LightMap {
+    fn default() -> Self {
+        Self {
+            baked: [[1.; GRID_SIZE.y as usize]; GRID_SIZE.x as usize],
+            levels: [[1.; GRID_SIZE.y as usize]; GRID_SIZE.x as usize],
+            dark_view_factor: 1.,
+        }
+    }
+}
+
+impl LightMap {
+    /// Light level at a location, in world units. Outside the map is dark.
+    pub fn level_at(&self, location: Vec2) -> f32 {
+        let coords = translation_to_grid_coords(location, TILE_SIZE);
+
+        if coords.x < 0 || coords.y < 0 || coords.x >= GRID_SIZE.x || coords.y >= GRID_SIZE.y {
+            return 0.;
+        }
+
+        self.levels[coords.x as usize][coords.y as usize]
+    }
+
+    /// Multiplier of the view range of enemies, for a player standing at a location.
+    pub fn visibility_at(&self, location: Vec2) -> f32 {
+        self.dark_view_factor + (1. - self.dark_view_factor) * self.level_at(location)
+    }
+}
+
+pub struct LightMapPlugin;
+
+impl Plugin for LightMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LightMap>().add_systems(
+            // After the movement of the frame, ready for the next AI transitions.
+            PostUpdate,
+            (bake_lamps, add_lanterns)
+                .chain()
+                .run_if(in_state(PlayingState::Playing)),
+        );
+    }
+}
+
+/// Walls block the light.
+fn is_lit_from(grid: &Grid<Tile>, source: Vec2, tile: Vec2) -> bool {
+    grid.walls_between(source, tile) == 0
+}
+
+fn tiles() -> impl Iterator<Item = (GridCoords, Vec2)> {
+    (0..GRID_SIZE.x).flat_map(|x| {
+        (0..GRID_SIZE.y).map(move |y| {
+            let coords = GridCoords::new(x, y);
+            (coords, grid_coords_to_translation(coords, TILE_SIZE))
+        })
+    })
+}
+
+fn bake_lamps(
+    mut light_map: ResMut<LightMap>,
+    lamps: Query<(&Transform, &Lamp)>,
+    changed_lamps: Query<(), Changed<Lamp>>,
+    mut removed_lamps: RemovedComponents<Lamp>,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    let removed = removed_lamps.read().count() > 0;
+    if !removed && changed_lamps.is_empty() && !grid.is_changed() && !tuning.is_changed() {
+        return;
+    }
+
+    let mut baked = [[tuning.ambient_light; GRID_SIZE.y as usize]; GRID_SIZE.x as usize];
+
+    for (transform, lamp) in &lamps {
+        let source = transform.translation.xy();
+
+        for (coords, tile) in tiles() {
+            let distance = source.distance(tile);
+            if distance >= lamp.radius || !is_lit_from(&grid, source, tile) {
+                continue;
+            }
+
+            let level = &mut baked[coords.x as usize][coords.y as usize];
+            *level = level.max(lamp.intensity * (1. - distance / lamp.radius));
+        }
+    }
+
+    light_map.baked = baked;
+    light_map.dark_view_factor = tuning.dark_view_factor;
+}
+
+/// Enemies carry a lantern lighting a cone in front of them.
+fn add_lanterns(
+    mut light_map: ResMut<LightMap>,
+    enemies: Query<(&Transform, &Aim, &EnemyTag), Without<Dead>>,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    let mut levels = light_map.baked;
+    let max_angle = tuning.lantern_half_angle.to_radians();
+
+    for (transform, aim, tag) in &enemies {
+        let source = transform.translation.xy();
+        let range = match tag {
+            EnemyTag::Investigator => tuning.investigator_lantern_range,
+            EnemyTag::Villager => tuning.villager_lantern_range,
+        };
+
+        let min = translation_to_grid_coords(source - Vec2::splat(range), TILE_SIZE);
+        let max = translation_to_grid_coords(source + Vec2::splat(range), TILE_SIZE);
+
+        for x in min.x.max(0)..=max.x.min(GRID_SIZE.x - 1) {
+            for y in min.y.max(0)..=max.y.min(GRID_SIZE.y - 1) {
+                let tile = grid_coords_to_translation(GridCoords::new(x, y), TILE_SIZE);
+                let distance = source.distance(tile);
+
+                // The tile the enemy stands on is always lit.
+                let in_cone = distance < TILE_SIZE.x as f32
+                    || (distance < range && aim.0.angle_between(tile - source).abs() < max_angle);
+                if !in_cone || !is_lit_from(&grid, source, tile) {
+                    continue;
+                }
+
+                levels[x as usize][y as usize] = 1.;
+            }
+        }
+    }
+
+    light_map.levels = levels;
+}
@@ -2,68 +2,202 @@
 
 mod ai;
 mod audio;
+mod campaign;
 mod config;
+#[cfg(feature = "debug_overlay")]
+mod debug_overlay;
 mod game_mode;
 mod grid;
+mod input_shaping;
 mod ldtk;
+mod light_map;
 mod menus;
 mod pathfinding;
 mod player_controller;
+mod player_input;
 mod rendering;
+mod replay;
+mod save;
+mod seed;
+mod sim;
+mod snapshot;
 mod states;
+mod tuning;
 mod utils;
 
+use std::{str::FromStr, time::Duration};
+
 use ai::AiPlugin;
 use audio::AudioPlugin;
-use bevy::prelude::*;
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_ecs_ldtk::LevelSelection;
+#[cfg(not(feature = "hot_reload"))]
 use bevy_embedded_assets::{EmbeddedAssetPlugin, PluginMode};
 // use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rand::plugin::EntropyPlugin;
 use bevy_rand::prelude::WyRand;
 use bevy_rapier2d::plugin::{NoUserData, RapierConfiguration, RapierPhysicsPlugin, TimestepMode};
 
-use config::PIXEL_PER_TILE;
+use campaign::CampaignPlugin;
+use config::{PIXEL_PER_TILE, SIM_TIMESTEP};
 use game_mode::GamePlugin;
 use grid::{GridPlugin, Tile};
 use ldtk::MyLdtkPlugin;
+use light_map::LightMapPlugin;
 use menus::MenusPlugin;
+use pathfinding::PathfindingPlugin;
 use player_controller::PlayerPlugin;
+use player_input::PlayerInputPlugin;
 use rendering::RenderingPlugin;
+use replay::{InputRecording, Recorder, Replay, ReplayPlugin};
+use save::SavePlugin;
+use seed::{RunSeed, SeedPlugin};
+use snapshot::SnapshotPlugin;
 use states::GameState;
+use tuning::TuningPlugin;
 
 fn main() {
-    App::new()
-        .add_plugins((
-            EmbeddedAssetPlugin {
-                mode: PluginMode::ReplaceDefault,
-            },
-            DefaultPlugins.set(ImagePlugin::default_nearest()),
+    let args: Vec<String> = std::env::args().collect();
+    let options = RunOptions::from_args(&args);
+
+    if args.iter().any(|arg| arg == "--headless") {
+        // Optional number of frames to simulate, otherwise run until the level is won or lost.
+        let frames = arg_value(&args, "--frames");
+
+        sim::run_headless(frames, options);
+        return;
+    }
+
+    let mut app = App::new();
+
+    app.add_plugins((
+        // Embedded assets can't be watched, so they are read from disk when hot reloading.
+        #[cfg(not(feature = "hot_reload"))]
+        EmbeddedAssetPlugin {
+            mode: PluginMode::ReplaceDefault,
+        },
+        DefaultPlugins.set(ImagePlugin::default_nearest()),
+        // #[cfg(debug_assertions)]
+        // WorldInspectorPlugin::new(), // for debug
+        RenderingPlugin,
+        AudioPlugin,
+        MenusPlugin,
+        SavePlugin,
+        SnapshotPlugin,
+        GameplayPlugin,
+        #[cfg(feature = "debug_overlay")]
+        debug_overlay::DebugOverlayPlugin,
+    ))
+    .init_state::<GameState>();
+    // .add_systems(Update, log_transitions::<GameState>)
+
+    if options.is_deterministic() {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            SIM_TIMESTEP,
+        )))
+        .insert_resource(rapier_configuration(TimestepMode::Fixed {
+            dt: SIM_TIMESTEP,
+            substeps: 1,
+        }));
+    } else {
+        app.insert_resource(rapier_configuration(TimestepMode::Variable {
+            max_dt: 1. / 60.,
+            time_scale: 1.,
+            substeps: 1,
+        }));
+    }
+
+    options.apply(&mut app);
+    app.run();
+}
+
+/// All the game rules (physics, level, player, AI and score), without anything that needs a window.
+/// Shared between the game and the headless [`sim::build_sim_app()`].
+struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
             RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXEL_PER_TILE),
-            // #[cfg(debug_assertions)]
-            // WorldInspectorPlugin::new(), // for debug
             EntropyPlugin::<WyRand>::default(),
-            RenderingPlugin,
-            AudioPlugin,
+            SeedPlugin,
+            TuningPlugin,
             GridPlugin::<Tile>::default(),
-            MenusPlugin,
             GamePlugin,
+            CampaignPlugin,
             MyLdtkPlugin,
+            PlayerInputPlugin,
             PlayerPlugin,
+            ReplayPlugin,
             AiPlugin,
-        ))
-        .insert_resource(RapierConfiguration {
-            gravity: Vec2::ZERO,
-            physics_pipeline_active: true,
-            query_pipeline_active: true,
-            timestep_mode: TimestepMode::Variable {
-                max_dt: 1. / 60.,
-                time_scale: 1.,
-                substeps: 1,
-            },
-            scaled_shape_subdivision: 10,
-            force_update_from_transform_changes: true,
-        })
-        .init_state::<GameState>()
-        // .add_systems(Update, log_transitions::<GameState>)
-        .run();
+            LightMapPlugin,
+            PathfindingPlugin,
+        ));
+    }
+}
+
+/// Options from the command line, shared between the game and the headless simulation.
+struct RunOptions {
+    seed: RunSeed,
+    record: Option<String>,
+    replay: Option<InputRecording>,
+}
+
+impl RunOptions {
+    fn from_args(args: &[String]) -> Self {
+        let replay =
+            arg_value::<String>(args, "--replay").and_then(|path| InputRecording::load(&path));
+
+        // A replay must use the seed it was recorded with.
+        let seed = replay
+            .as_ref()
+            .map(|recording| recording.seed)
+            .or_else(|| arg_value(args, "--seed"))
+            .map(RunSeed::fixed)
+            .unwrap_or_default();
+
+        Self {
+            seed,
+            record: arg_value(args, "--record"),
+            replay,
+        }
+    }
+
+    /// Recording and replaying need a fixed timestep to play out the same.
+    fn is_deterministic(&self) -> bool {
+        self.record.is_some() || self.replay.is_some()
+    }
+
+    fn apply(self, app: &mut App) {
+        app.insert_resource(self.seed);
+
+        if let Some(path) = self.record {
+            app.insert_resource(Recorder::new(path));
+        }
+
+        if let Some(recording) = self.replay {
+            // The game picks it from the menu, but the simulation starts playing right away.
+            app.insert_resource(LevelSelection::Identifier(recording.level.clone()));
+            app.insert_resource(Replay::new(recording));
+        }
+    }
+}
+
+/// Parse the value following `name` in the command line arguments.
+fn arg_value<T: FromStr>(args: &[String], name: &str) -> Option<T> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .and_then(|value| value.parse().ok())
+}
+
+fn rapier_configuration(timestep_mode: TimestepMode) -> RapierConfiguration {
+    RapierConfiguration {
+        gravity: Vec2::ZERO,
+        physics_pipeline_active: true,
+        query_pipeline_active: true,
+        timestep_mode,
+        scaled_shape_subdivision: 10,
+        force_update_from_transform_changes: true,
+    }
 }
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+
+use crate::{
+    player_input::{Action, ActionMap},
+    rendering::PIXEL_PERFECT_LAYERS,
+    states::MainMenuState,
+};
+
+use super::{spawn_text_button, ButtonTag, UiFocus};
+
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+pub struct ControlsTag;
+
+/// Waiting for a key or gamepad button to bind to the [`Action`].
+#[derive(Resource)]
+pub struct Rebinding(pub Action);
+
+pub fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            ControlsTag,
+            PIXEL_PERFECT_LAYERS,
+        ))
+        .with_children(|parent| {
+            // Labels are filled in by `update_labels()`.
+            for (order, action) in Action::ALL.iter().enumerate() {
+                let focus = if order == 0 {
+                    UiFocus::Focused
+                } else {
+                    UiFocus::None
+                };
+
+                spawn_text_button(
+                    parent,
+                    action.name(),
+                    ButtonTag::Rebind(*action),
+                    order as i32,
+                    focus,
+                );
+            }
+
+            let order = Action::ALL.len() as i32;
+            spawn_text_button(
+                parent,
+                "Reset to defaults",
+                ButtonTag::ResetControls,
+                order,
+                UiFocus::None,
+            );
+            spawn_text_button(parent, "Back", ButtonTag::Back, order + 1, UiFocus::None);
+        });
+}
+
+pub fn cleanup(mut commands: Commands, query: Query<Entity, With<ControlsTag>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.remove_resource::<Rebinding>();
+}
+
+pub fn button_system(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &ButtonTag), (Changed<Interaction>, With<Button>)>,
+    mut action_map: ResMut<ActionMap>,
+    mut next_menu_state: ResMut<NextState<MainMenuState>>,
+) {
+    for (interaction, tag) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match tag {
+                ButtonTag::Rebind(action) => {
+                    commands.insert_resource(Rebinding(*action));
+                }
+                ButtonTag::ResetControls => {
+                    *action_map = ActionMap::default();
+                }
+                ButtonTag::Back => {
+                    next_menu_state.set(MainMenuState::Title);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Bind the next key or gamepad button pressed, or cancel with the ones bound to [`Action::Pause`].
+pub fn capture_rebinding(
+    mut commands: Commands,
+    rebinding: Res<Rebinding>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    mut action_map: ResMut<ActionMap>,
+) {
+    // Skip the press that started the rebinding.
+    if rebinding.is_added() {
+        return;
+    }
+
+    let pause = action_map.binding(Action::Pause);
+
+    if let Some(key) = keys.get_just_pressed().next() {
+        if pause.key != Some(*key) {
+            action_map.bind_key(rebinding.0, *key);
+        }
+    } else if let Some(button) = buttons.get_just_pressed().next() {
+        if pause.button != Some(button.button_type) {
+            action_map.bind_button(rebinding.0, button.button_type);
+        }
+    } else {
+        return;
+    }
+
+    commands.remove_resource::<Rebinding>();
+}
+
+pub fn update_labels(
+    buttons: Query<(&ButtonTag, &Children)>,
+    mut texts: Query<&mut Text>,
+    action_map: Res<ActionMap>,
+    rebinding: Option<Res<Rebinding>>,
+) {
+    for (tag, children) in &buttons {
+        let ButtonTag::Rebind(action) = tag else {
+            continue;
+        };
+
+        let label = match &rebinding {
+            Some(rebinding) if rebinding.0 == *action => {
+                format!("{}: press a key or button", action.name())
+            }
+            _ => {
+                let binding = action_map.binding(*action);
+                let key = binding
+                    .key
+                    .map(|key| format!("{:?}", key))
+                    .unwrap_or("-".to_string());
+                let button = binding
+                    .button
+                    .map(|button| format!("{:?}", button))
+                    .unwrap_or("-".to_string());
+
+                format!("{}: {} / {}", action.name(), key, button)
+            }
+        };
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+}
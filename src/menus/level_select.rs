@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::LevelSelection;
+
+use crate::{
+    campaign::Campaign,
+    rendering::PIXEL_PERFECT_LAYERS,
+    states::{GameState, MainMenuState},
+};
+
+use super::{spawn_text_button, ButtonTag, UiFocus, TEXT_BUTTON_PRESSED_COLOR};
+
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+pub struct LevelSelectTag;
+
+pub fn setup(mut commands: Commands, campaign: Res<Campaign>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            LevelSelectTag,
+            PIXEL_PERFECT_LAYERS,
+        ))
+        .with_children(|parent| {
+            let mut order = 0;
+
+            for (index, identifier) in campaign.levels.iter().enumerate() {
+                // Best score as "killed / total" and best time, once the level was played at least once.
+                let best = campaign
+                    .record(index)
+                    .map(|record| {
+                        let time = record
+                            .best_time
+                            .map(|time| format!("  {:.1}s", time))
+                            .unwrap_or_default();
+
+                        format!(
+                            "  {}/{}{}",
+                            record.best_villagers_killed, record.total_villagers, time
+                        )
+                    })
+                    .unwrap_or_default();
+
+                if campaign.is_unlocked(index) {
+                    let focus = if order == 0 {
+                        UiFocus::Focused
+                    } else {
+                        UiFocus::None
+                    };
+
+                    spawn_text_button(
+                        parent,
+                        format!("{}{}", identifier, best),
+                        ButtonTag::Level(index),
+                        order,
+                        focus,
+                    );
+
+                    order += 1;
+                } else {
+                    // Locked levels can't be focused.
+                    parent.spawn(
+                        TextBundle::from_section(
+                            format!("{} (locked)", identifier),
+                            TextStyle {
+                                font_size: 40.0,
+                                color: TEXT_BUTTON_PRESSED_COLOR,
+                                ..default()
+                            },
+                        )
+                        .with_style(Style {
+                            padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                            margin: UiRect::bottom(Val::Px(10.0)),
+                            ..default()
+                        }),
+                    );
+                }
+            }
+
+            spawn_text_button(parent, "Back", ButtonTag::Back, order, UiFocus::None);
+        });
+}
+
+pub fn cleanup(mut commands: Commands, query: Query<Entity, With<LevelSelectTag>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn button_system(
+    interaction_query: Query<(&Interaction, &ButtonTag), (Changed<Interaction>, With<Button>)>,
+    mut campaign: ResMut<Campaign>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_menu_state: ResMut<NextState<MainMenuState>>,
+) {
+    for (interaction, tag) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match tag {
+                ButtonTag::Level(index) => {
+                    campaign.select(*index);
+                    *level_selection = campaign.level_selection();
+                    next_state.set(GameState::Reset);
+                }
+                ButtonTag::Back => {
+                    next_menu_state.set(MainMenuState::Title);
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,8 +1,17 @@
 use bevy::prelude::*;
+use bevy_ecs_ldtk::LevelSelection;
 
-use crate::{audio::AudioSetting, rendering::PIXEL_PERFECT_LAYERS, states::GameState};
+use crate::{
+    audio::AudioSetting,
+    campaign::Campaign,
+    rendering::PIXEL_PERFECT_LAYERS,
+    snapshot,
+    states::{GameState, MainMenuState},
+};
 
-use super::{AudioControllerTag, ButtonTag, UiElementsHandles, UiFocus, UiFocusOrder};
+use super::{
+    spawn_text_button, AudioControllerTag, ButtonTag, UiElementsHandles, UiFocus, UiFocusOrder,
+};
 
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
@@ -69,6 +78,29 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                     ));
                 });
 
+            // Focus orders need to follow each other for the navigation to wrap around.
+            let mut order = 1;
+
+            if snapshot::has_snapshot() {
+                spawn_text_button(
+                    parent,
+                    "Continue",
+                    ButtonTag::Continue,
+                    order,
+                    UiFocus::None,
+                );
+                order += 1;
+            }
+
+            spawn_text_button(parent, "Levels", ButtonTag::Levels, order, UiFocus::None);
+            spawn_text_button(
+                parent,
+                "Controls",
+                ButtonTag::Controls,
+                order + 1,
+                UiFocus::None,
+            );
+
             if !cfg!(target_family = "wasm") {
                 let style = Style {
                     width: Val::Px(29. * 3.),
@@ -83,7 +115,7 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                             ..default()
                         },
                         ButtonTag::Quit,
-                        UiFocusOrder(1),
+                        UiFocusOrder(order + 2),
                         UiFocus::None,
                     ))
                     .with_children(|parent| {
@@ -159,11 +191,15 @@ pub fn cleanup(mut commands: Commands, main_menu: Query<Entity, With<MainMenuTag
 }
 
 pub fn button_system(
+    mut commands: Commands,
     mut interaction_query: Query<
         (&mut Interaction, &ButtonTag),
         (Changed<Interaction>, With<Button>),
     >,
     mut next_state: ResMut<NextState<GameState>>,
+    mut next_menu_state: ResMut<NextState<MainMenuState>>,
+    campaign: Res<Campaign>,
+    mut level_selection: ResMut<LevelSelection>,
     mut audio_settings: ResMut<AudioSetting>,
     mut exit: EventWriter<AppExit>,
 ) {
@@ -171,7 +207,17 @@ pub fn button_system(
         if *interaction == Interaction::Pressed {
             match tag {
                 ButtonTag::Play => {
-                    next_state.set(GameState::Playing);
+                    *level_selection = campaign.level_selection();
+                    next_state.set(GameState::Reset);
+                }
+                ButtonTag::Continue => {
+                    commands.add(snapshot::resume);
+                }
+                ButtonTag::Levels => {
+                    next_menu_state.set(MainMenuState::LevelSelect);
+                }
+                ButtonTag::Controls => {
+                    next_menu_state.set(MainMenuState::Controls);
                 }
                 ButtonTag::Quit => {
                     exit.send(AppExit::Success);
@@ -1,14 +1,22 @@
 use bevy::{input::gamepad::GamepadEvent, prelude::*, utils::HashMap};
 use iyes_progress::{prelude::AssetsLoading, ProgressPlugin};
 
-use crate::states::{GameState, PlayingState};
-
+use crate::{
+    config::STICK_MENU_THRESHOLD,
+    input_shaping::StickShaping,
+    player_input::{Action, ActionMap, ActiveGamepad},
+    states::{GameState, MainMenuState, PlayingState},
+};
+
+mod controls;
+mod level_select;
 mod lose_menu;
 mod main_menu;
 mod pause_menu;
 mod ui;
 mod win_menu;
 
+use controls::Rebinding;
 pub use ui::{PlayerLivesUiTag, VillagerKilledUiTag, VillagerTotalUiTag};
 
 #[derive(Reflect, Clone, Component)]
@@ -19,6 +27,15 @@ enum ButtonTag {
     Reset,
     Resume,
     Audio,
+    NextLevel,
+    Levels,
+    Level(usize),
+    Back,
+    Suspend,
+    Continue,
+    Controls,
+    Rebind(Action),
+    ResetControls,
 }
 
 #[derive(Reflect, Clone)]
@@ -47,6 +64,10 @@ enum UiFocus {
 #[reflect(Component)]
 pub struct AudioControllerTag;
 
+const TEXT_BUTTON_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const TEXT_BUTTON_FOCUSED_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const TEXT_BUTTON_PRESSED_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
 pub struct MenusPlugin;
 
 impl Plugin for MenusPlugin {
@@ -56,21 +77,33 @@ impl Plugin for MenusPlugin {
                 .continue_to(GameState::MainMenu)
                 .track_assets(),
         )
+        .add_sub_state::<MainMenuState>()
         .add_systems(OnEnter(GameState::Loading), setup)
         .add_systems(OnEnter(PlayingState::Lose), lose_menu::setup)
         .add_systems(OnExit(PlayingState::Lose), lose_menu::cleanup)
-        .add_systems(OnEnter(GameState::MainMenu), main_menu::setup)
-        .add_systems(OnExit(GameState::MainMenu), main_menu::cleanup)
+        .add_systems(OnEnter(MainMenuState::Title), main_menu::setup)
+        .add_systems(OnExit(MainMenuState::Title), main_menu::cleanup)
+        .add_systems(OnEnter(MainMenuState::LevelSelect), level_select::setup)
+        .add_systems(OnExit(MainMenuState::LevelSelect), level_select::cleanup)
+        .add_systems(OnEnter(MainMenuState::Controls), controls::setup)
+        .add_systems(OnExit(MainMenuState::Controls), controls::cleanup)
         .add_systems(OnEnter(PlayingState::Pause), pause_menu::setup)
         .add_systems(OnExit(PlayingState::Pause), pause_menu::cleanup)
         .add_systems(OnEnter(PlayingState::Win), win_menu::setup)
         .add_systems(OnExit(PlayingState::Win), win_menu::cleanup)
         .add_systems(OnEnter(GameState::Playing), ui::setup)
+        .add_systems(OnExit(GameState::Playing), ui::cleanup)
         .add_systems(
             Update,
             (
                 lose_menu::button_system.run_if(in_state(PlayingState::Lose)),
-                main_menu::button_system.run_if(in_state(GameState::MainMenu)),
+                main_menu::button_system.run_if(in_state(MainMenuState::Title)),
+                level_select::button_system.run_if(in_state(MainMenuState::LevelSelect)),
+                controls::button_system.run_if(
+                    in_state(MainMenuState::Controls).and_then(not(resource_exists::<Rebinding>)),
+                ),
+                controls::capture_rebinding.run_if(resource_exists::<Rebinding>),
+                controls::update_labels.run_if(in_state(MainMenuState::Controls)),
                 pause_menu::button_system.run_if(in_state(PlayingState::Pause)),
                 win_menu::button_system.run_if(in_state(PlayingState::Win)),
                 (
@@ -85,7 +118,9 @@ impl Plugin for MenusPlugin {
                             .or_else(in_state(GameState::MainMenu))
                             .or_else(in_state(PlayingState::Pause))
                             .or_else(in_state(PlayingState::Win)),
-                    ),
+                    )
+                    // Any key or button is going to be bound instead.
+                    .run_if(not(resource_exists::<Rebinding>)),
             ),
         );
     }
@@ -146,24 +181,76 @@ fn button_interaction(
         (Or<(Changed<Interaction>, Changed<UiFocus>)>, With<Button>),
     >,
     mut images: Query<&mut TextureAtlas>,
+    mut texts: Query<&mut Text>,
 ) {
     for (interaction, focus, children) in &interaction_query {
         for child in children {
-            let Ok(mut atlas) = images.get_mut(*child) else {
-                continue;
-            };
-
-            if *interaction == Interaction::Pressed || *focus == UiFocus::Pressed {
-                atlas.index = 2;
-            } else if *interaction == Interaction::Hovered || *focus == UiFocus::Focused {
-                atlas.index = 1;
-            } else {
-                atlas.index = 0;
+            // Image buttons have their 3 states in the atlas, text buttons change color.
+            if let Ok(mut atlas) = images.get_mut(*child) {
+                if *interaction == Interaction::Pressed || *focus == UiFocus::Pressed {
+                    atlas.index = 2;
+                } else if *interaction == Interaction::Hovered || *focus == UiFocus::Focused {
+                    atlas.index = 1;
+                } else {
+                    atlas.index = 0;
+                }
+            } else if let Ok(mut text) = texts.get_mut(*child) {
+                let color = if *interaction == Interaction::Pressed || *focus == UiFocus::Pressed {
+                    TEXT_BUTTON_PRESSED_COLOR
+                } else if *interaction == Interaction::Hovered || *focus == UiFocus::Focused {
+                    TEXT_BUTTON_FOCUSED_COLOR
+                } else {
+                    TEXT_BUTTON_COLOR
+                };
+
+                for section in &mut text.sections {
+                    section.style.color = color;
+                }
             }
         }
     }
 }
 
+/// Button with a text label instead of an image, for things that don't have art.
+fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    text: impl Into<String>,
+    tag: ButtonTag,
+    order: i32,
+    focus: UiFocus,
+) {
+    let color = if focus == UiFocus::Focused {
+        TEXT_BUTTON_FOCUSED_COLOR
+    } else {
+        TEXT_BUTTON_COLOR
+    };
+
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            tag,
+            UiFocusOrder(order),
+            focus,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 40.0,
+                    color,
+                    ..default()
+                },
+            ));
+        });
+}
+
 fn navigation(direction: i32, mut buttons: Query<(&UiFocusOrder, &mut UiFocus), With<Button>>) {
     // Find current focused item. Also get min/max index.
     let mut current: Option<i32> = None;
@@ -211,20 +298,35 @@ fn focus_press(buttons: &mut Query<(&mut Interaction, &mut UiFocus), With<Button
     }
 }
 
+/// Whether the key bound to the [`Action`] was just pressed.
+fn action_key_just_pressed(
+    action: Action,
+    action_map: &ActionMap,
+    keyboard: &ButtonInput<KeyCode>,
+) -> bool {
+    action_map
+        .binding(action)
+        .key
+        .is_some_and(|key| keyboard.just_pressed(key))
+}
+
 fn keyboard_navigation(
     buttons: Query<(&UiFocusOrder, &mut UiFocus), With<Button>>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    action_map: Res<ActionMap>,
 ) {
     let mut direction: Option<i32> = None;
 
-    if keyboard.just_pressed(KeyCode::KeyS)
+    if action_key_just_pressed(Action::MoveDown, &action_map, &keyboard)
         || keyboard.just_pressed(KeyCode::ArrowDown)
         || keyboard.just_pressed(KeyCode::Tab)
     {
         direction = Some(1);
     }
 
-    if keyboard.just_pressed(KeyCode::KeyW) || keyboard.just_pressed(KeyCode::ArrowUp) {
+    if action_key_just_pressed(Action::MoveUp, &action_map, &keyboard)
+        || keyboard.just_pressed(KeyCode::ArrowUp)
+    {
         direction = Some(-1);
     }
 
@@ -236,8 +338,11 @@ fn keyboard_navigation(
 fn keyboard_focus_press(
     mut buttons: Query<(&mut Interaction, &mut UiFocus), With<Button>>,
     input: Res<ButtonInput<KeyCode>>,
+    action_map: Res<ActionMap>,
 ) {
-    if input.just_pressed(KeyCode::Enter) || input.just_pressed(KeyCode::Space) {
+    if input.just_pressed(KeyCode::Enter)
+        || action_key_just_pressed(Action::Interact, &action_map, &input)
+    {
         focus_press(&mut buttons);
     }
 }
@@ -245,17 +350,15 @@ fn keyboard_focus_press(
 fn gamepad_navigation(
     buttons: Query<(&UiFocusOrder, &mut UiFocus), With<Button>>,
     mut evr_gamepad: EventReader<GamepadEvent>,
+    active_gamepad: Res<ActiveGamepad>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    shaping: Res<StickShaping>,
+    mut stick_held: Local<bool>,
 ) {
     let mut direction: Option<i32> = None;
 
     for ev in evr_gamepad.read() {
         match ev {
-            GamepadEvent::Axis(_ev_axis) => {
-                // Would need to debounce it.
-                // if ev_axis.axis_type == GamepadAxisType::LeftStickY && ev_axis.value.abs() > 0.8 {
-                //     direction = Some(ev_axis.value.signum() as i32);
-                // }
-            }
             GamepadEvent::Button(ev_button) => {
                 if ev_button.value > 0. {
                     match ev_button.button_type {
@@ -273,6 +376,33 @@ fn gamepad_navigation(
         }
     }
 
+    // Move once per push of the stick, it has to go back to rest before moving again.
+    let stick = active_gamepad
+        .0
+        .map(|gamepad| {
+            let axis = |axis_type| {
+                gamepad_axes
+                    .get(GamepadAxis::new(gamepad, axis_type))
+                    .unwrap_or(0.)
+            };
+
+            shaping.shape(Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                axis(GamepadAxisType::LeftStickY),
+            ))
+        })
+        .unwrap_or(Vec2::ZERO);
+
+    if stick.y.abs() > STICK_MENU_THRESHOLD {
+        if !*stick_held {
+            // Up goes to the previous item.
+            direction = Some(-stick.y.signum() as i32);
+        }
+        *stick_held = true;
+    } else {
+        *stick_held = false;
+    }
+
     if let Some(direction) = direction {
         navigation(direction, buttons);
     }
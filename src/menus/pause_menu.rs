@@ -2,16 +2,20 @@ use bevy::prelude::*;
 
 use crate::{
     audio::AudioSetting,
+    seed::RunSeed,
+    snapshot,
     states::{GameState, PlayingState},
 };
 
-use super::{AudioControllerTag, ButtonTag, UiElementsHandles, UiFocus, UiFocusOrder};
+use super::{
+    spawn_text_button, AudioControllerTag, ButtonTag, UiElementsHandles, UiFocus, UiFocusOrder,
+};
 
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
 pub struct PauseMenuTag;
 
-pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
+pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>, seed: Res<RunSeed>) {
     commands
         .spawn((
             NodeBundle {
@@ -41,6 +45,22 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                 ..default()
             });
 
+            // To include in bug reports, so the run can be reproduced.
+            parent.spawn(
+                TextBundle::from_section(
+                    format!("Seed: {}", seed.current),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(0.6, 0.6, 0.6),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(40.0)),
+                    ..default()
+                }),
+            );
+
             let style = Style {
                 width: Val::Px(51. * 6.),
                 height: Val::Px(17. * 6.),
@@ -106,6 +126,8 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                     ));
                 });
 
+            spawn_text_button(parent, "Suspend", ButtonTag::Suspend, 2, UiFocus::None);
+
             if !cfg!(target_family = "wasm") {
                 let style = Style {
                     width: Val::Px(29. * 3.),
@@ -120,7 +142,7 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                             ..default()
                         },
                         ButtonTag::Quit,
-                        UiFocusOrder(2),
+                        UiFocusOrder(3),
                         UiFocus::None,
                     ))
                     .with_children(|parent| {
@@ -195,6 +217,7 @@ pub fn cleanup(mut commands: Commands, query: Query<Entity, With<PauseMenuTag>>)
 }
 
 pub fn button_system(
+    mut commands: Commands,
     mut interaction_query: Query<
         (&mut Interaction, &ButtonTag),
         (Changed<Interaction>, With<Button>),
@@ -216,6 +239,9 @@ pub fn button_system(
                 ButtonTag::Resume => {
                     next_state_play.set(PlayingState::Playing);
                 }
+                ButtonTag::Suspend => {
+                    commands.add(snapshot::suspend);
+                }
                 ButtonTag::Audio => {
                     audio_settings.next_audio_level();
                     *interaction = Interaction::Hovered;
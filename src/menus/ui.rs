@@ -198,3 +198,9 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                 });
         });
 }
+
+pub fn cleanup(mut commands: Commands, query: Query<Entity, With<UiTag>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,13 +1,16 @@
 use bevy::prelude::*;
 
 use super::{AudioControllerTag, ButtonTag, UiElementsHandles, UiFocus, UiFocusOrder};
-use crate::{audio::AudioSetting, states::GameState};
+use crate::{audio::AudioSetting, campaign::Campaign, states::GameState};
 
 #[derive(Reflect, Clone, Component)]
 #[reflect(Component)]
 pub struct WinMenuTag;
 
-pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
+pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>, campaign: Res<Campaign>) {
+    // Only offer to continue if there is a level after this one.
+    let has_next_level = campaign.has_next_level();
+
     commands
         .spawn((
             NodeBundle {
@@ -37,6 +40,45 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                 ..default()
             });
 
+            if has_next_level {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(32. * 6.),
+                                height: Val::Px(17. * 6.),
+                                margin: UiRect::bottom(Val::Px(20.0)),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        ButtonTag::NextLevel,
+                        UiFocusOrder(0),
+                        UiFocus::Focused,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            ImageBundle {
+                                style: Style {
+                                    width: Val::Px(32. * 6.),
+                                    height: Val::Px(17. * 6.),
+                                    ..default()
+                                },
+                                image: UiImage::new(
+                                    ui_elements.0.get("play").unwrap().image.clone(),
+                                ),
+                                ..default()
+                            },
+                            TextureAtlas::from(
+                                ui_elements.0.get("play").unwrap().atlas.clone().unwrap(),
+                            ),
+                        ));
+                    });
+            }
+
+            // Buttons after "Next level" are shifted in the focus order.
+            let offset = has_next_level as i32;
+
             let style = Style {
                 width: Val::Px(51. * 3.),
                 height: Val::Px(17. * 3.),
@@ -50,8 +92,12 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                         ..default()
                     },
                     ButtonTag::Reset,
-                    UiFocusOrder(0),
-                    UiFocus::Focused,
+                    UiFocusOrder(offset),
+                    if has_next_level {
+                        UiFocus::None
+                    } else {
+                        UiFocus::Focused
+                    },
                 ))
                 .with_children(|parent| {
                     parent.spawn((
@@ -82,7 +128,7 @@ pub fn setup(mut commands: Commands, ui_elements: Res<UiElementsHandles>) {
                             ..default()
                         },
                         ButtonTag::Quit,
-                        UiFocusOrder(1),
+                        UiFocusOrder(1 + offset),
                         UiFocus::None,
                     ))
                     .with_children(|parent| {
@@ -174,6 +220,9 @@ pub fn button_system(
                 ButtonTag::Reset => {
                     next_state.set(GameState::Reset);
                 }
+                ButtonTag::NextLevel => {
+                    next_state.set(GameState::NextLevel);
+                }
                 ButtonTag::Audio => {
                     audio_settings.next_audio_level();
                     *interaction = Interaction::Hovered;
@@ -1,5 +1,7 @@
 // Pathfinding
 // "Stolen" from https://www.youtube.com/watch?v=QTUEyAZmdv4
+// Entities following a moving goal go through the [`PathPlanner`], which keeps their current path while the goal
+// stays on the same tile, and only plans a few paths per frame.
 
 use std::collections::VecDeque;
 
@@ -9,7 +11,9 @@ use pathfinding::prelude::astar;
 
 use crate::{
     config::GRID_SIZE,
-    grid::{Grid, GridLocation},
+    grid::{Grid, GridLocation, Tile},
+    states::PlayingState,
+    tuning::GameTuning,
 };
 
 pub struct PathfindingError;
@@ -18,6 +22,75 @@ pub struct PathfindingError;
 #[reflect(Component)]
 pub struct Path {
     pub steps: VecDeque<GridCoords>,
+    pub goal: GridCoords,
+}
+
+impl Path {
+    /// A wall was added on the way since the path was planned.
+    pub fn is_blocked<T>(&self, grid: &Grid<T>) -> bool {
+        self.steps
+            .iter()
+            .any(|step| grid.occupied(&GridLocation::from(*step)))
+    }
+}
+
+/// Ask the [`PathPlanner`] for a [`Path`] to `goal`.
+/// Inserted again every frame by entities following a moving goal, it's removed once handled.
+#[derive(Clone, Reflect, Component)]
+#[reflect(Component)]
+pub struct PathRequest {
+    pub goal: GridCoords,
+}
+
+/// No path could be found to `goal`, the last unreachable [`PathRequest`].
+/// Requests for the same goal aren't planned again until the walls change.
+#[derive(Clone, Reflect, Component)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct PathUnreachable {
+    pub goal: GridCoords,
+}
+
+impl PathUnreachable {
+    pub fn is_goal(unreachable: Option<&PathUnreachable>, goal: &GridCoords) -> bool {
+        unreachable.is_some_and(|unreachable| unreachable.goal == *goal)
+    }
+}
+
+/// Entities waiting for a [`Path`], planned oldest first within the budget of each frame.
+#[derive(Resource, Default)]
+pub struct PathPlanner {
+    queue: VecDeque<Entity>,
+    replans: u32,
+    cache_hits: u32,
+    elapsed: f32,
+}
+
+/// Updated every second.
+#[derive(Resource, Reflect, Default, Clone, Debug)]
+#[reflect(Resource)]
+pub struct PathfindingMetrics {
+    pub replans_per_second: f32,
+    pub cache_hits_per_second: f32,
+    /// Requests waiting for a later frame.
+    pub queued: usize,
+}
+
+pub struct PathfindingPlugin;
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PathPlanner>()
+            .init_resource::<PathfindingMetrics>()
+            .register_type::<PathfindingMetrics>()
+            .add_systems(
+                // After the AI requested paths during the frame, ready to be followed next frame.
+                PostUpdate,
+                (plan_paths, update_metrics)
+                    .chain()
+                    .run_if(in_state(PlayingState::Playing)),
+            );
+    }
 }
 
 impl<T> Grid<T> {
@@ -40,7 +113,10 @@ impl<T> Grid<T> {
             // Remove the first node, as it's always the one the entity is on
             steps.pop_front();
             // Return a path with the steps
-            Ok(Path { steps: steps })
+            Ok(Path {
+                steps: steps,
+                goal: *goal,
+            })
         } else {
             Err(PathfindingError)
         }
@@ -51,13 +127,15 @@ pub fn neumann_neighbors<T>(grid: &Grid<T>, location: &GridCoords) -> Vec<GridCo
     let (x, y) = (location.x, location.y);
 
     let mut sucessors = Vec::new();
-    if let Some(left) = x.checked_sub(1) {
+    if x > 0 {
+        let left = x - 1;
         let location = GridCoords::new(left as i32, y as i32);
         if !grid.occupied(&GridLocation::from(location)) {
             sucessors.push(location);
         }
     }
-    if let Some(down) = y.checked_sub(1) {
+    if y > 0 {
+        let down = y - 1;
         let location = GridCoords::new(x as i32, down as i32);
         if !grid.occupied(&GridLocation::from(location)) {
             sucessors.push(location);
@@ -79,3 +157,162 @@ pub fn neumann_neighbors<T>(grid: &Grid<T>, location: &GridCoords) -> Vec<GridCo
     }
     sucessors
 }
+
+/// Keep the current [`Path`] if it still leads to the goal, or the [`PathUnreachable`] if the goal still can't be reached,
+/// otherwise queue the request. Then plan as many queued requests as `tuning.pathfinding_budget` allows.
+fn plan_paths(
+    mut commands: Commands,
+    requests: Query<(
+        Entity,
+        &GridCoords,
+        &PathRequest,
+        Option<&Path>,
+        Option<&PathUnreachable>,
+    )>,
+    mut planner: ResMut<PathPlanner>,
+    grid: Res<Grid<Tile>>,
+    tuning: Res<GameTuning>,
+) {
+    for (entity, coords, request, path, unreachable) in &requests {
+        // Already there, a path to another goal would lead away.
+        if *coords == request.goal {
+            planner.cache_hits += 1;
+            planner.queue.retain(|queued| *queued != entity);
+            let mut entity = commands.entity(entity);
+            entity.remove::<(PathRequest, PathUnreachable)>();
+            if path.is_some_and(|path| path.goal != request.goal) {
+                entity.remove::<Path>();
+            }
+            continue;
+        }
+
+        let cached = path.is_some_and(|path| {
+            path.goal == request.goal && !path.steps.is_empty() && !path.is_blocked(&grid)
+        }) || (PathUnreachable::is_goal(unreachable, &request.goal)
+            && !grid.is_changed());
+
+        if cached {
+            planner.cache_hits += 1;
+            commands.entity(entity).remove::<PathRequest>();
+        } else if !planner.queue.contains(&entity) {
+            planner.queue.push_back(entity);
+        }
+    }
+
+    let mut budget = tuning.pathfinding_budget;
+    while budget > 0 {
+        let Some(entity) = planner.queue.pop_front() else {
+            break;
+        };
+        // The request was dropped (like when leaving an AI state).
+        let Ok((_, coords, request, _, unreachable)) = requests.get(entity) else {
+            continue;
+        };
+
+        budget -= 1;
+        planner.replans += 1;
+
+        match grid.path_to(coords, &request.goal) {
+            Ok(path) => {
+                commands.entity(entity).insert(path).remove::<PathRequest>();
+                // Still known unreachable when it's another goal, like a fallback.
+                if PathUnreachable::is_goal(unreachable, &request.goal) {
+                    commands.entity(entity).remove::<PathUnreachable>();
+                }
+            }
+            Err(_) => {
+                commands
+                    .entity(entity)
+                    .insert(PathUnreachable { goal: request.goal })
+                    .remove::<PathRequest>();
+            }
+        }
+    }
+}
+
+fn update_metrics(
+    mut planner: ResMut<PathPlanner>,
+    mut metrics: ResMut<PathfindingMetrics>,
+    time: Res<Time>,
+) {
+    planner.elapsed += time.delta_seconds();
+    if planner.elapsed < 1. {
+        return;
+    }
+
+    *metrics = PathfindingMetrics {
+        replans_per_second: planner.replans as f32 / planner.elapsed,
+        cache_hits_per_second: planner.cache_hits as f32 / planner.elapsed,
+        queued: planner.queue.len(),
+    };
+    debug!("{:?}", *metrics);
+
+    planner.replans = 0;
+    planner.cache_hits = 0;
+    planner.elapsed = 0.;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn goal_change_onto_own_tile_drops_stale_path() {
+        let mut world = World::new();
+        world.init_resource::<PathPlanner>();
+        world.init_resource::<Grid<Tile>>();
+        world.insert_resource(GameTuning::default());
+
+        let here = GridCoords::new(2, 2);
+        let entity = world
+            .spawn((
+                here,
+                Path {
+                    steps: VecDeque::from([GridCoords::new(3, 2), GridCoords::new(4, 2)]),
+                    goal: GridCoords::new(4, 2),
+                },
+                PathRequest { goal: here },
+            ))
+            .id();
+
+        world.run_system_once(plan_paths);
+
+        let entity = world.entity(entity);
+        assert!(!entity.contains::<Path>());
+        assert!(!entity.contains::<PathRequest>());
+    }
+
+    #[test]
+    fn unreachable_goal_is_not_planned_again() {
+        let mut world = World::new();
+        world.init_resource::<PathPlanner>();
+        world.init_resource::<Grid<Tile>>();
+        world.insert_resource(GameTuning::default());
+        let plan_paths = world.register_system(plan_paths);
+
+        // Walled in.
+        let goal = GridCoords::new(5, 5);
+        let wall = world.spawn_empty().id();
+        let mut grid = world.resource_mut::<Grid<Tile>>();
+        for (x, y) in [(4, 5), (6, 5), (5, 4), (5, 6)] {
+            grid[&GridLocation::new(x, y)] = Some(wall);
+        }
+
+        let entity = world
+            .spawn((GridCoords::new(2, 2), PathRequest { goal }))
+            .id();
+        world.run_system(plan_paths).unwrap();
+
+        world.entity_mut(entity).insert(PathRequest { goal });
+        world.run_system(plan_paths).unwrap();
+
+        let entity = world.entity(entity);
+        assert_eq!(world.resource::<PathPlanner>().replans, 1);
+        assert!(entity
+            .get::<PathUnreachable>()
+            .is_some_and(|unreachable| unreachable.goal == goal));
+        assert!(!entity.contains::<PathRequest>());
+    }
+}
@@ -1,22 +1,29 @@
 use bevy::prelude::*;
+use bevy_ecs_ldtk::{utils::translation_to_grid_coords, GridCoords};
 use bevy_rapier2d::{plugin::RapierContext, prelude::*};
 
 use crate::{
-    ai::{Chased, Dead},
+    ai::{Chased, Dead, Noise, TransitionExt},
     config::{
-        INVESTIGATOR_HEARING_RANGE, NOISE_MAKER_ANIMATION, PLAYER_ANIMATION_ATTACK,
-        PLAYER_ANIMATION_HIDDING, PLAYER_ANIMATION_IDLE, PLAYER_ANIMATION_RUN, PLAYER_SPEED,
+        NOISE_MAKER_ANIMATION, PIXEL_PER_TILE, PLAYER_ANIMATION_ATTACK, PLAYER_ANIMATION_HIDDING,
+        PLAYER_ANIMATION_IDLE, PLAYER_ANIMATION_RUN, TILE_SIZE,
     },
+    grid::{Grid, Tile},
     ldtk::{
         animation::new_animation,
         entities::{
-            hidding_spot::HiddingSpotExit, player::PlayerTag, Aim, AnimationConfig, EnemyTag,
-            InteractibleTag, InteractionPossible, NoiseMakerInvestigateTarget,
-            NoiseMakerReTriggerable, NoiseMakerTriggerable, NoiseMakerTriggered,
+            add_corpse_sensor,
+            hidding_spot::{HiddingSpot, HiddingSpotExit},
+            player::{PlayerTag, PlayerVelocity},
+            Aim, AnimationConfig, CorpseHidden, EnemyTag, InteractibleTag, InteractionPossible,
+            NoiseMakerInvestigateTarget, NoiseMakerReTriggerable, NoiseMakerTriggerable,
         },
     },
+    light_map::LightMap,
+    player_input::{Action, ActionMap, ActiveGamepad, ActiveGamepadDisconnected, PlayerInput},
     rendering::Cameras,
     states::{GameState, PlayingState},
+    tuning::GameTuning,
 };
 
 #[derive(Reflect, Clone, Component)]
@@ -24,18 +31,55 @@ use crate::{
 #[component(storage = "SparseSet")]
 pub struct PlayerIsHidding(pub Vec2);
 
+/// An investigator saw the player getting into the hidding spot.
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct SeenHidding;
+
+/// The player is dragging a corpse behind.
+#[derive(Reflect, Clone, Component)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Dragging(pub Entity);
+
+/// The active gamepad was disconnected during the level, pause as soon as the player is in control again.
+#[derive(Resource, Default)]
+struct GamepadDisconnected(bool);
+
+/// Where the player was the last frame [`track_velocity`] ran, forgotten when play resumes.
+#[derive(Resource, Default)]
+struct LastPlayerLocation(Option<Vec2>);
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::Playing), cleanup)
+        app.init_resource::<GamepadDisconnected>()
+            .init_resource::<LastPlayerLocation>()
+            .add_systems(
+                OnExit(GameState::Playing),
+                (cleanup, clear_gamepad_disconnected),
+            )
+            .add_systems(OnEnter(PlayingState::Pause), clear_gamepad_disconnected)
+            .add_systems(OnEnter(PlayingState::Playing), clear_last_player_location)
+            .add_systems(
+                Update,
+                // In every state of the level, so a disconnect during a cutscene isn't missed.
+                track_gamepad_disconnect
+                    .before(pause_on_gamepad_disconnect)
+                    .run_if(in_state(GameState::Playing)),
+            )
             .add_systems(
                 Update,
                 (
                     setup_camera,
                     move_player,
                     interaction_pressed,
+                    drag_corpse,
+                    track_velocity,
                     player_is_chased,
+                    pause_on_gamepad_disconnect,
                 )
                     .run_if(in_state(PlayingState::Playing)),
             )
@@ -88,87 +132,32 @@ fn move_player(
             &AnimationConfig,
             &mut KinematicCharacterController,
             &mut Transform,
+            Has<Dragging>,
         ),
         (With<PlayerTag>, Without<PlayerIsHidding>),
     >,
     mut cameras: Query<&mut Transform, (With<Cameras>, Without<PlayerTag>)>,
     time: Res<Time>,
-    input: Res<ButtonInput<KeyCode>>,
-    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
-    gamepad_axes: Res<Axis<GamepadAxis>>,
+    player_input: Res<PlayerInput>,
+    tuning: Res<GameTuning>,
 ) {
-    let Ok((entity, animation, mut controller, mut transform)) = player.get_single_mut() else {
-        return;
-    };
-
-    let Ok(mut cameras) = cameras.get_single_mut() else {
+    let Ok((entity, animation, mut controller, mut transform, dragging)) = player.get_single_mut()
+    else {
         return;
     };
 
-    let mut direction = Vec2::ZERO;
-
-    if input.pressed(KeyCode::KeyW)
-        || gamepad_buttons.pressed(GamepadButton {
-            gamepad: Gamepad::new(0),
-            button_type: GamepadButtonType::DPadUp,
-        })
-    {
-        direction.y += 1.0;
-    }
-
-    if input.pressed(KeyCode::KeyS)
-        || gamepad_buttons.pressed(GamepadButton {
-            gamepad: Gamepad::new(0),
-            button_type: GamepadButtonType::DPadDown,
-        })
-    {
-        direction.y -= 1.0;
-    }
-
-    if input.pressed(KeyCode::KeyA)
-        || gamepad_buttons.pressed(GamepadButton {
-            gamepad: Gamepad::new(0),
-            button_type: GamepadButtonType::DPadLeft,
-        })
-    {
-        direction.x -= 1.0;
-    }
-
-    if input.pressed(KeyCode::KeyD)
-        || gamepad_buttons.pressed(GamepadButton {
-            gamepad: Gamepad::new(0),
-            button_type: GamepadButtonType::DPadRight,
-        })
-    {
-        direction.x += 1.0;
-    }
+    // There is no camera when running headless.
+    let mut cameras = cameras.get_single_mut().ok();
 
-    let axis_lx = GamepadAxis {
-        gamepad: Gamepad::new(0),
-        axis_type: GamepadAxisType::LeftStickX,
-    };
+    let direction = player_input.movement;
 
-    let axis_ly = GamepadAxis {
-        gamepad: Gamepad::new(0),
-        axis_type: GamepadAxisType::LeftStickY,
+    // Corpses are heavy.
+    let speed = match dragging {
+        true => tuning.player_speed * tuning.drag_speed_factor,
+        false => tuning.player_speed,
     };
 
-    let mut direction = direction.normalize_or_zero();
-
-    if let (Some(x), Some(y)) = (gamepad_axes.get(axis_lx), gamepad_axes.get(axis_ly)) {
-        // Filter "drift" or whatever this is.
-        let x = if x.abs() > 0.1 { x } else { 0. };
-        let y = if y.abs() > 0.1 { y } else { 0. };
-
-        direction = Vec2::new(x, y);
-
-        // Normalize if above 1.
-        if x + y > 1. {
-            direction /= x + y;
-        }
-    }
-
-    let move_delta = direction * PLAYER_SPEED * time.delta_seconds();
+    let move_delta = direction * speed * time.delta_seconds();
 
     controller.translation = Some(move_delta);
 
@@ -189,25 +178,35 @@ fn move_player(
         let angle = (direction.y.atan2(direction.x).to_degrees() / 45.).round() * 45.;
 
         transform.rotation = Quat::from_euler(EulerRot::XYZ, 0., 0., angle.to_radians());
-        cameras.rotation = Quat::from_euler(EulerRot::XYZ, 0., 0., -angle.to_radians());
+        if let Some(cameras) = &mut cameras {
+            cameras.rotation = Quat::from_euler(EulerRot::XYZ, 0., 0., -angle.to_radians());
+        }
     }
 }
 
 fn interaction_pressed(
     mut commands: Commands,
-    input: Res<ButtonInput<KeyCode>>,
-    gamepad: Res<ButtonInput<GamepadButton>>,
+    player_input: Res<PlayerInput>,
     mut player: Query<
         (
             Entity,
             &mut Transform,
             Option<&InteractionPossible>,
             Option<&PlayerIsHidding>,
+            Option<&Dragging>,
         ),
         (With<PlayerTag>, Without<Chased>),
     >,
-    enemies: Query<(Entity, &Transform, &EnemyTag), Without<PlayerTag>>,
-    hidding_spots: Query<(&Transform, &HiddingSpotExit), Without<PlayerTag>>,
+    hidding_spots: Query<(&Transform, &HiddingSpotExit, &HiddingSpot), Without<PlayerTag>>,
+    mut corpses: Query<
+        (&mut Transform, Has<CorpseHidden>),
+        (
+            With<Dead>,
+            Without<PlayerTag>,
+            Without<HiddingSpotExit>,
+            Without<NoiseMakerTriggerable>,
+        ),
+    >,
     noise_makers: Query<
         (
             Entity,
@@ -217,37 +216,79 @@ fn interaction_pressed(
         ),
         (With<NoiseMakerTriggerable>, Without<PlayerTag>),
     >,
+    villagers: Query<
+        (&Transform, &GridCoords),
+        (With<EnemyTag>, Without<Dead>, Without<PlayerTag>),
+    >,
+    mut noises: EventWriter<Noise>,
+    tuning: Res<GameTuning>,
 ) {
-    // If the Space bar was just pressed
-    if !(input.just_pressed(KeyCode::Space)
-        || gamepad.just_pressed(GamepadButton {
-            gamepad: Gamepad::new(0),
-            button_type: GamepadButtonType::South,
-        }))
-    {
+    // If interaction was just pressed
+    if !player_input.interact {
         return;
     }
 
     // and we can query the player
-    let Ok((player, mut player_transform, player_interaction, player_hidding)) =
+    let Ok((player, mut player_transform, player_interaction, player_hidding, dragging)) =
         player.get_single_mut()
     else {
         return;
     };
 
+    // Corpses already stashed in a hidding spot.
+    let occupants = |hidding_spot_transform: &Transform| {
+        corpses
+            .iter()
+            .filter(|(corpse_transform, hidden)| {
+                *hidden
+                    && corpse_transform.translation.xy() == hidding_spot_transform.translation.xy()
+            })
+            .count()
+    };
+
+    // If dragging a corpse, either stash it in the hidding spot nearby (if there is room), or drop it.
+    if let Some(Dragging(corpse)) = dragging {
+        commands.entity(player).remove::<Dragging>();
+
+        let hidding_spot = player_interaction
+            .filter(|interaction| {
+                matches!(interaction.interactibe_type, InteractibleTag::HiddingSpot)
+            })
+            .and_then(|interaction| hidding_spots.get(interaction.entity).ok())
+            .filter(|(transform, _, hidding_spot)| !hidding_spot.is_full(occupants(transform)));
+
+        if let Some((hidding_spot_transform, _, _)) = hidding_spot {
+            if let Ok((mut corpse_transform, _)) = corpses.get_mut(*corpse) {
+                corpse_transform.translation = hidding_spot_transform
+                    .translation
+                    .xy()
+                    .extend(corpse_transform.translation.z);
+            }
+
+            commands.entity(*corpse).insert(CorpseHidden);
+            commands.entity(player).remove::<InteractionPossible>();
+        } else {
+            add_corpse_sensor(&mut commands, *corpse);
+        }
+
+        return;
+    }
+
     // If already hidding
     if let Some(hidding) = player_hidding {
-        // Move player to exit
-        // IMPROVEME: Tweening between positions
-        // Needs to set Z to 0 (instead of the actual 12) otherwise Rapier moves it up for whatever reason.
-        player_transform.translation = hidding.0.extend(0.);
-
-        // Enable back collision (not sure it's actually doing something) and remove PlayerIsHidding tag.
-        commands
-            .entity(player)
-            .insert(CollisionGroups::new(Group::GROUP_1, Group::GROUP_1))
-            .insert(new_animation(PLAYER_ANIMATION_IDLE))
-            .remove::<PlayerIsHidding>();
+        if let Some((hidding_spot_transform, _, _)) = hidding_spots
+            .iter()
+            .find(|(_, exit, hidding_spot)| exit.0 == hidding.0 && hidding_spot.noisy)
+        {
+            noises.send(Noise {
+                location: hidding_spot_transform.translation.xy(),
+                target: translation_to_grid_coords(hidding.0, TILE_SIZE),
+                loudness: tuning.noisy_hidding_spot_loudness,
+                occluded: true,
+            });
+        }
+
+        leave_hidding_spot(&mut commands, player, &mut player_transform, hidding);
     } else {
         // If there is a possible interaction
         let Some(interaction) = player_interaction else {
@@ -256,12 +297,24 @@ fn interaction_pressed(
 
         match interaction.interactibe_type {
             InteractibleTag::HiddingSpot => {
-                // If we can get the position of hiding spot from the interaction
-                let Ok((hidding_spot_transform, exit_location)) =
+                // If we can get the position of hiding spot from the interaction, and there is room left
+                let Ok((hidding_spot_transform, exit_location, hidding_spot)) =
                     hidding_spots.get(interaction.entity)
                 else {
                     return;
                 };
+                if hidding_spot.is_full(occupants(hidding_spot_transform)) {
+                    return;
+                }
+
+                if hidding_spot.noisy {
+                    noises.send(Noise {
+                        location: hidding_spot_transform.translation.xy(),
+                        target: translation_to_grid_coords(exit_location.0, TILE_SIZE),
+                        loudness: tuning.noisy_hidding_spot_loudness,
+                        occluded: true,
+                    });
+                }
 
                 // Disable collisions (not sure it's actually doing something), so it won't collide with the hidding spot and add PlayerIsHidding tag.
                 commands
@@ -308,18 +361,13 @@ fn interaction_pressed(
                         .remove::<NoiseMakerTriggerable>();
                 }
 
-                // Add NoiseTriggered to all Investigators in range
-                for (investigator, transform, tag) in &enemies {
-                    if *tag == EnemyTag::Investigator {
-                        if noise_maker_location.distance(transform.translation.xy())
-                            <= INVESTIGATOR_HEARING_RANGE
-                        {
-                            commands
-                                .entity(investigator)
-                                .insert(NoiseMakerTriggered(noise_maker_investigate_coords.0));
-                        }
-                    }
-                }
+                // Investigators in range go check the noise maker's target.
+                noises.send(Noise {
+                    location: noise_maker_location,
+                    target: noise_maker_investigate_coords.0,
+                    loudness: 1.,
+                    occluded: false,
+                });
 
                 commands
                     .entity(player)
@@ -327,9 +375,26 @@ fn interaction_pressed(
 
                 commands.entity(player).remove::<InteractionPossible>();
             }
+            InteractibleTag::Corpse => {
+                // Start dragging, remove the sensor and prompt so hidding spots can be interacted with.
+                commands.entity(player).insert(Dragging(interaction.entity));
+                commands.entity(interaction.entity).despawn_descendants();
+                commands.entity(player).remove::<InteractionPossible>();
+            }
             InteractibleTag::Villager => {
+                // Killing isn't quiet.
+                if let Ok((villager_transform, villager_coords)) = villagers.get(interaction.entity)
+                {
+                    noises.send(Noise {
+                        location: villager_transform.translation.xy(),
+                        target: *villager_coords,
+                        loudness: tuning.kill_loudness,
+                        occluded: true,
+                    });
+                }
+
                 // Set dead state (this also handle animation and cleanup).
-                commands.entity(interaction.entity).insert(Dead);
+                commands.entity(interaction.entity).transition_to(Dead);
 
                 // Play player kill animation
                 commands
@@ -342,18 +407,68 @@ fn interaction_pressed(
     }
 }
 
+/// Smoothed over a few frames, the physics doesn't move the player every frame.
+/// Jumping in and out of hidding spots isn't movement.
+fn track_velocity(
+    mut player: Query<(&Transform, &mut PlayerVelocity, Has<PlayerIsHidding>), With<PlayerTag>>,
+    mut last_location: ResMut<LastPlayerLocation>,
+    time: Res<Time>,
+) {
+    let Ok((transform, mut velocity, hidding)) = player.get_single_mut() else {
+        last_location.0 = None;
+        return;
+    };
+
+    if hidding {
+        velocity.0 = Vec2::ZERO;
+        last_location.0 = None;
+        return;
+    }
+
+    let location = transform.translation.xy();
+    if let Some(last_location) = last_location.0 {
+        if time.delta_seconds() > 0. {
+            let current = (location - last_location) / time.delta_seconds();
+            velocity.0 = velocity.0.lerp(current, 0.25);
+        }
+    }
+    last_location.0 = Some(location);
+}
+
+/// The player may have been moved while not playing (respawned, cutscene), that isn't movement either.
+fn clear_last_player_location(mut last_location: ResMut<LastPlayerLocation>) {
+    last_location.0 = None;
+}
+
+/// Move the player out of its hidding spot, either on its own or pulled out by an investigator.
+pub fn leave_hidding_spot(
+    commands: &mut Commands,
+    player: Entity,
+    player_transform: &mut Transform,
+    hidding: &PlayerIsHidding,
+) {
+    // Move player to exit
+    // IMPROVEME: Tweening between positions
+    // Needs to set Z to 0 (instead of the actual 12) otherwise Rapier moves it up for whatever reason.
+    player_transform.translation = hidding.0.extend(0.);
+
+    // Enable back collision (not sure it's actually doing something) and remove PlayerIsHidding tag.
+    commands
+        .entity(player)
+        .insert(CollisionGroups::new(Group::GROUP_1, Group::GROUP_1))
+        .insert(new_animation(PLAYER_ANIMATION_IDLE))
+        .remove::<(PlayerIsHidding, SeenHidding)>();
+}
+
 fn toggle_pause(
     input: Res<ButtonInput<KeyCode>>,
     gamepad: Res<ButtonInput<GamepadButton>>,
+    action_map: Res<ActionMap>,
+    active_gamepad: Res<ActiveGamepad>,
     current_state: Res<State<PlayingState>>,
     mut next_state: ResMut<NextState<PlayingState>>,
 ) {
-    if input.just_pressed(KeyCode::Escape)
-        || gamepad.just_pressed(GamepadButton {
-            gamepad: Gamepad::new(0),
-            button_type: GamepadButtonType::Start,
-        })
-    {
+    if action_map.just_pressed(Action::Pause, &input, &gamepad, active_gamepad.0) {
         if *current_state.get() == PlayingState::Playing {
             next_state.set(PlayingState::Pause);
         } else if *current_state.get() == PlayingState::Pause {
@@ -362,15 +477,64 @@ fn toggle_pause(
     }
 }
 
+fn track_gamepad_disconnect(
+    mut events: EventReader<ActiveGamepadDisconnected>,
+    mut disconnected: ResMut<GamepadDisconnected>,
+) {
+    if events.read().next().is_some() {
+        disconnected.0 = true;
+    }
+}
+
+/// Don't let the game run without the player being able to control it.
+fn pause_on_gamepad_disconnect(
+    disconnected: Res<GamepadDisconnected>,
+    mut next_state: ResMut<NextState<PlayingState>>,
+) {
+    if disconnected.0 {
+        next_state.set(PlayingState::Pause);
+    }
+}
+
+/// Paused, for the disconnect or not, the player can pick a controller before resuming.
+fn clear_gamepad_disconnected(mut disconnected: ResMut<GamepadDisconnected>) {
+    disconnected.0 = false;
+}
+
 pub fn player_is_chased(
     mut commands: Commands,
-    player: Query<Entity, (With<PlayerTag>, Added<Chased>)>,
+    player: Query<(Entity, Option<&Dragging>), (With<PlayerTag>, Added<Chased>)>,
 ) {
-    let Ok(player) = player.get_single() else {
+    let Ok((player, dragging)) = player.get_single() else {
         return;
     };
 
     commands.entity(player).remove::<InteractionPossible>();
+
+    // No time to carry a corpse.
+    if let Some(Dragging(corpse)) = dragging {
+        commands.entity(player).remove::<Dragging>();
+        add_corpse_sensor(&mut commands, *corpse);
+    }
+}
+
+/// The dragged corpse follows right behind the player.
+fn drag_corpse(
+    player: Query<(&Transform, &Dragging), With<PlayerTag>>,
+    mut corpses: Query<&mut Transform, Without<PlayerTag>>,
+) {
+    let Ok((player_transform, Dragging(corpse))) = player.get_single() else {
+        return;
+    };
+
+    let Ok(mut corpse_transform) = corpses.get_mut(*corpse) else {
+        return;
+    };
+
+    let behind = player_transform.rotation * Vec3::NEG_X * PIXEL_PER_TILE * 0.75;
+    corpse_transform.translation = (player_transform.translation + behind)
+        .xy()
+        .extend(corpse_transform.translation.z);
 }
 
 pub fn is_player_visible(
@@ -381,9 +545,12 @@ pub fn is_player_visible(
     other_aim: Aim,
     max_distance: f32,
     max_angle: f32,
+    light_map: &LightMap,
     rapier_context: &Res<RapierContext>,
 ) -> bool {
     let max_angle = max_angle.to_radians();
+    // The player is harder to see in the dark.
+    let max_distance = max_distance * light_map.visibility_at(player_location);
     // Check if player is within range.
     if other_location.distance(player_location) < max_distance {
         let dir = (player_location - other_location).normalize();
@@ -406,3 +573,21 @@ pub fn is_player_visible(
 
     false
 }
+
+/// Same as [`is_player_visible`], for something without a collider (like a corpse).
+/// Only walls of the [`Grid`] can block the view.
+pub fn is_location_visible(
+    location: Vec2,
+    other_location: Vec2,
+    other_aim: Aim,
+    max_distance: f32,
+    max_angle: f32,
+    light_map: &LightMap,
+    grid: &Grid<Tile>,
+) -> bool {
+    let max_distance = max_distance * light_map.visibility_at(location);
+
+    other_location.distance(location) < max_distance
+        && other_aim.0.angle_between(location - other_location).abs() < max_angle.to_radians()
+        && grid.walls_between(other_location, location) == 0
+}
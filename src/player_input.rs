@@ -0,0 +1,377 @@
+// Player input
+// Gameplay reads what the player wants to do from [`PlayerInput`] instead of reading the devices directly,
+// so it can come from the keyboard and gamepad, or be fed back from a replay.
+// Keys and gamepad buttons are bound to an [`Action`] through the [`ActionMap`], which can be rebound in the menus.
+// The keyboard always works, along with the [`ActiveGamepad`]: the last connected or used one.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent},
+        InputSystem,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::input_shaping::StickShaping;
+
+#[derive(
+    Serialize, Deserialize, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug,
+)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Interact,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Interact,
+        Action::Pause,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Interact => "Interact",
+            Action::Pause => "Pause",
+        }
+    }
+
+    fn default_binding(&self) -> Binding {
+        let (key, button) = match self {
+            Action::MoveUp => (KeyCode::KeyW, GamepadButtonType::DPadUp),
+            Action::MoveDown => (KeyCode::KeyS, GamepadButtonType::DPadDown),
+            Action::MoveLeft => (KeyCode::KeyA, GamepadButtonType::DPadLeft),
+            Action::MoveRight => (KeyCode::KeyD, GamepadButtonType::DPadRight),
+            Action::Interact => (KeyCode::Space, GamepadButtonType::South),
+            Action::Pause => (KeyCode::Escape, GamepadButtonType::Start),
+        };
+
+        Binding {
+            key: Some(key),
+            button: Some(button),
+        }
+    }
+}
+
+/// A key and a gamepad button for the same [`Action`].
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+pub struct Binding {
+    pub key: Option<KeyCode>,
+    pub button: Option<GamepadButtonType>,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ActionMap {
+    bindings: BTreeMap<Action, Binding>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .map(|action| (*action, action.default_binding()))
+                .collect(),
+        }
+    }
+}
+
+impl ActionMap {
+    /// Actions missing from a saved map keep their default binding.
+    pub fn binding(&self, action: Action) -> Binding {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    /// An action already using the key gets the previous key of `action`, so both stay bound.
+    pub fn bind_key(&mut self, action: Action, key: KeyCode) {
+        let mut binding = self.binding(action);
+        let previous = binding.key.replace(key);
+
+        for other in Action::ALL.into_iter().filter(|other| *other != action) {
+            let mut other_binding = self.binding(other);
+            if other_binding.key == Some(key) {
+                other_binding.key = previous;
+                self.bindings.insert(other, other_binding);
+            }
+        }
+
+        self.bindings.insert(action, binding);
+    }
+
+    /// An action already using the button gets the previous button of `action`, so both stay bound.
+    pub fn bind_button(&mut self, action: Action, button: GamepadButtonType) {
+        let mut binding = self.binding(action);
+        let previous = binding.button.replace(button);
+
+        for other in Action::ALL.into_iter().filter(|other| *other != action) {
+            let mut other_binding = self.binding(other);
+            if other_binding.button == Some(button) {
+                other_binding.button = previous;
+                self.bindings.insert(other, other_binding);
+            }
+        }
+
+        self.bindings.insert(action, binding);
+    }
+
+    pub fn pressed(
+        &self,
+        action: Action,
+        keys: &ButtonInput<KeyCode>,
+        buttons: &ButtonInput<GamepadButton>,
+        gamepad: Option<Gamepad>,
+    ) -> bool {
+        let binding = self.binding(action);
+
+        binding.key.is_some_and(|key| keys.pressed(key))
+            || binding
+                .button
+                .zip(gamepad)
+                .is_some_and(|(button, gamepad)| {
+                    buttons.pressed(GamepadButton::new(gamepad, button))
+                })
+    }
+
+    pub fn just_pressed(
+        &self,
+        action: Action,
+        keys: &ButtonInput<KeyCode>,
+        buttons: &ButtonInput<GamepadButton>,
+        gamepad: Option<Gamepad>,
+    ) -> bool {
+        let binding = self.binding(action);
+
+        binding.key.is_some_and(|key| keys.just_pressed(key))
+            || binding
+                .button
+                .zip(gamepad)
+                .is_some_and(|(button, gamepad)| {
+                    buttons.just_pressed(GamepadButton::new(gamepad, button))
+                })
+    }
+}
+
+#[derive(Resource, Reflect, Clone, Copy, Default, Debug)]
+#[reflect(Resource)]
+pub struct PlayerInput {
+    /// Where to move, with a length of at most 1.
+    pub movement: Vec2,
+    /// Interaction was pressed this frame.
+    pub interact: bool,
+}
+
+/// Gamepad used by the player, `None` when there is no gamepad connected.
+#[derive(Resource, Reflect, Clone, Copy, Default, Debug)]
+#[reflect(Resource)]
+pub struct ActiveGamepad(pub Option<Gamepad>);
+
+/// The [`ActiveGamepad`] was disconnected.
+#[derive(Event)]
+pub struct ActiveGamepadDisconnected;
+
+/// Systems filling [`PlayerInput`], before anything reads it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSet;
+
+pub struct PlayerInputPlugin;
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerInput>()
+            .init_resource::<ActionMap>()
+            .init_resource::<ActiveGamepad>()
+            .init_resource::<StickShaping>()
+            .register_type::<PlayerInput>()
+            .register_type::<StickShaping>()
+            .register_type::<ActiveGamepad>()
+            .add_event::<ActiveGamepadDisconnected>()
+            .configure_sets(PreUpdate, PlayerInputSet.after(InputSystem))
+            .add_systems(
+                PreUpdate,
+                (track_gamepads, read_devices)
+                    .chain()
+                    .in_set(PlayerInputSet),
+            );
+    }
+}
+
+/// Follow gamepads being connected, disconnected, and switch to the one being used.
+fn track_gamepads(
+    mut active_gamepad: ResMut<ActiveGamepad>,
+    mut connections: EventReader<GamepadConnectionEvent>,
+    mut disconnected: EventWriter<ActiveGamepadDisconnected>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+) {
+    for event in connections.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                info!("Gamepad {} connected: {}", event.gamepad.id, info.name);
+
+                if active_gamepad.0.is_none() {
+                    active_gamepad.0 = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                info!("Gamepad {} disconnected", event.gamepad.id);
+
+                if active_gamepad.0 == Some(event.gamepad) {
+                    // Fall back to any other connected one.
+                    active_gamepad.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+                    disconnected.send(ActiveGamepadDisconnected);
+                }
+            }
+        }
+    }
+
+    // Switch to the gamepad with a button pressed or a stick pushed.
+    let used = gamepad_buttons
+        .get_just_pressed()
+        .map(|button| button.gamepad)
+        .chain(gamepads.iter().filter(|gamepad| {
+            let axis = |axis_type| {
+                gamepad_axes
+                    .get(GamepadAxis::new(*gamepad, axis_type))
+                    .unwrap_or(0.)
+            };
+
+            Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                axis(GamepadAxisType::LeftStickY),
+            )
+            .length()
+                > 0.5
+        }))
+        .next();
+
+    if let Some(gamepad) = used {
+        if active_gamepad.0 != Some(gamepad) {
+            info!("Switched to gamepad {}", gamepad.id);
+            active_gamepad.0 = Some(gamepad);
+        }
+    }
+}
+
+pub fn read_devices(
+    mut player_input: ResMut<PlayerInput>,
+    action_map: Res<ActionMap>,
+    active_gamepad: Res<ActiveGamepad>,
+    input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    shaping: Res<StickShaping>,
+) {
+    let gamepad = active_gamepad.0;
+    let pressed = |action| action_map.pressed(action, &input, &gamepad_buttons, gamepad);
+
+    let mut direction = Vec2::ZERO;
+
+    if pressed(Action::MoveUp) {
+        direction.y += 1.0;
+    }
+
+    if pressed(Action::MoveDown) {
+        direction.y -= 1.0;
+    }
+
+    if pressed(Action::MoveLeft) {
+        direction.x -= 1.0;
+    }
+
+    if pressed(Action::MoveRight) {
+        direction.x += 1.0;
+    }
+
+    let mut direction = direction.normalize_or_zero();
+
+    let sticks = gamepad.and_then(|gamepad| {
+        Some((
+            gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))?,
+            gamepad_axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))?,
+        ))
+    });
+
+    if let Some((x, y)) = sticks {
+        let stick = shaping.shape(Vec2::new(x, y));
+
+        // Keep the keyboard and DPad direction while the stick is at rest.
+        if stick != Vec2::ZERO {
+            direction = stick;
+        }
+    }
+
+    player_input.movement = direction;
+
+    player_input.interact =
+        action_map.just_pressed(Action::Interact, &input, &gamepad_buttons, gamepad);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binding_a_used_key_swaps_it() {
+        let mut action_map = ActionMap::default();
+
+        action_map.bind_key(Action::Interact, KeyCode::KeyW);
+
+        assert_eq!(
+            action_map.binding(Action::Interact).key,
+            Some(KeyCode::KeyW)
+        );
+        assert_eq!(action_map.binding(Action::MoveUp).key, Some(KeyCode::Space));
+    }
+
+    #[test]
+    fn binding_a_used_button_swaps_it() {
+        let mut action_map = ActionMap::default();
+
+        action_map.bind_button(Action::MoveUp, GamepadButtonType::South);
+
+        assert_eq!(
+            action_map.binding(Action::MoveUp).button,
+            Some(GamepadButtonType::South)
+        );
+        assert_eq!(
+            action_map.binding(Action::Interact).button,
+            Some(GamepadButtonType::DPadUp)
+        );
+    }
+
+    #[test]
+    fn binding_a_free_key_keeps_others() {
+        let mut action_map = ActionMap::default();
+
+        action_map.bind_key(Action::Interact, KeyCode::KeyE);
+
+        assert_eq!(
+            action_map.binding(Action::Interact).key,
+            Some(KeyCode::KeyE)
+        );
+        for action in Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::Interact)
+        {
+            assert_eq!(action_map.binding(action), action.default_binding());
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Input recording and replay
+// `--record <file>` stores the player input of every playing frame, `--replay <file>` feeds it back.
+// Both run with a fixed timestep, so with the recorded seed the level plays out exactly the same.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_ecs_ldtk::LevelSelection;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    campaign::{update_levels, Campaign},
+    player_input::{read_devices, PlayerInput, PlayerInputSet},
+    seed::RunSeed,
+    states::{GameState, PlayingState},
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct RecordedFrame {
+    delta: f32, // In seconds
+    movement: [f32; 2],
+    interact: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct InputRecording {
+    pub seed: u64,
+    pub level: String,
+    frames: Vec<RecordedFrame>,
+}
+
+impl InputRecording {
+    pub fn load(path: &str) -> Option<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) => {
+                warn!("Could not read recording {}: {}", path, error);
+                return None;
+            }
+        };
+
+        match ron::de::from_str(&content) {
+            Ok(recording) => Some(recording),
+            Err(error) => {
+                warn!("Could not parse recording {}: {}", path, error);
+                None
+            }
+        }
+    }
+
+    fn save(&self, path: &str) {
+        let result = ron::ser::to_string(self)
+            .map_err(|error| error.to_string())
+            .and_then(|content| std::fs::write(path, content).map_err(|error| error.to_string()));
+
+        match result {
+            Ok(()) => info!("Recorded {} frames to {}", self.frames.len(), path),
+            Err(error) => warn!("Could not write recording {}: {}", path, error),
+        }
+    }
+}
+
+/// Records the current level to the given file.
+#[derive(Resource)]
+pub struct Recorder {
+    path: String,
+    recording: InputRecording,
+}
+
+impl Recorder {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            recording: InputRecording::default(),
+        }
+    }
+}
+
+/// Replaces the devices input with the recorded one, until the end of the recording.
+#[derive(Resource)]
+pub struct Replay {
+    recording: InputRecording,
+    next_frame: usize,
+}
+
+impl Replay {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+        }
+    }
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            start_replay
+                .after(update_levels)
+                .run_if(resource_exists::<Replay>),
+        )
+        .add_systems(
+            OnEnter(PlayingState::IntroScene),
+            start_recording.run_if(resource_exists::<Recorder>),
+        )
+        .add_systems(
+            OnEnter(PlayingState::Win),
+            save_recording.run_if(resource_exists::<Recorder>),
+        )
+        .add_systems(
+            OnEnter(PlayingState::Lose),
+            save_recording.run_if(resource_exists::<Recorder>),
+        )
+        .add_systems(
+            PreUpdate,
+            (
+                replay_input
+                    .in_set(PlayerInputSet)
+                    .after(read_devices)
+                    .run_if(resource_exists::<Replay>),
+                record_input
+                    .after(PlayerInputSet)
+                    .run_if(resource_exists::<Recorder>),
+            )
+                .run_if(in_state(PlayingState::Playing)),
+        );
+    }
+}
+
+/// Skip the menus and play the recorded level.
+fn start_replay(
+    replay: Res<Replay>,
+    mut campaign: ResMut<Campaign>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Some(index) = campaign
+        .levels
+        .iter()
+        .position(|identifier| *identifier == replay.recording.level)
+    {
+        campaign.select(index);
+    }
+
+    *level_selection = LevelSelection::Identifier(replay.recording.level.clone());
+    next_state.set(GameState::Reset);
+}
+
+fn start_recording(mut recorder: ResMut<Recorder>) {
+    recorder.recording = InputRecording::default();
+}
+
+fn record_input(mut recorder: ResMut<Recorder>, player_input: Res<PlayerInput>, time: Res<Time>) {
+    recorder.recording.frames.push(RecordedFrame {
+        delta: time.delta_seconds(),
+        movement: player_input.movement.to_array(),
+        interact: player_input.interact,
+    });
+}
+
+fn save_recording(mut recorder: ResMut<Recorder>, seed: Res<RunSeed>, campaign: Res<Campaign>) {
+    recorder.recording.seed = seed.current;
+    recorder.recording.level = campaign
+        .levels
+        .get(campaign.current)
+        .cloned()
+        .unwrap_or_default();
+
+    recorder.recording.save(&recorder.path);
+}
+
+fn replay_input(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    mut player_input: ResMut<PlayerInput>,
+    mut time_update: ResMut<TimeUpdateStrategy>,
+) {
+    let Some(frame) = replay.recording.frames.get(replay.next_frame).copied() else {
+        info!("Replay finished after {} frames", replay.next_frame);
+        commands.remove_resource::<Replay>();
+        return;
+    };
+
+    player_input.movement = Vec2::from_array(frame.movement);
+    player_input.interact = frame.interact;
+
+    // Time for this frame is already set, prepare the next one.
+    replay.next_frame += 1;
+    if let Some(next) = replay.recording.frames.get(replay.next_frame) {
+        *time_update = TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(next.delta));
+    }
+}
@@ -0,0 +1,157 @@
+// Save data
+// Persistent progress and settings (audio, key bindings), written to the platform data directory on native,
+// and to `localStorage` on the web.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::{AudioLevels, AudioSetting},
+    campaign::{Campaign, LevelRecord},
+    player_input::ActionMap,
+    states::GameState,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIRECTORY: &str = "SpookyGame";
+#[cfg(target_arch = "wasm32")]
+const SAVE_KEY_PREFIX: &str = "spooky_game_";
+
+const SAVE_NAME: &str = "save";
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SaveData {
+    audio: AudioLevels,
+    levels: BTreeMap<String, LevelRecord>,
+    villagers_killed: usize,
+    controls: ActionMap,
+}
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Loading), load)
+            .add_systems(
+                Update,
+                save.run_if(not(in_state(GameState::Loading))).run_if(
+                    resource_changed::<AudioSetting>
+                        .or_else(resource_changed::<Campaign>)
+                        .or_else(resource_changed::<ActionMap>),
+                ),
+            );
+    }
+}
+
+fn load(
+    mut audio_settings: ResMut<AudioSetting>,
+    mut campaign: ResMut<Campaign>,
+    mut action_map: ResMut<ActionMap>,
+) {
+    let Some(content) = read(SAVE_NAME) else {
+        return;
+    };
+
+    let data = match ron::de::from_str::<SaveData>(&content) {
+        Ok(data) => data,
+        Err(error) => {
+            warn!("Could not parse save data: {}", error);
+            return;
+        }
+    };
+
+    audio_settings.0 = data.audio;
+    campaign.records = data.levels.into_iter().collect();
+    campaign.lifetime_villagers_killed = data.villagers_killed;
+    *action_map = data.controls;
+}
+
+fn save(audio_settings: Res<AudioSetting>, campaign: Res<Campaign>, action_map: Res<ActionMap>) {
+    let data = SaveData {
+        audio: audio_settings.0,
+        levels: campaign
+            .records
+            .iter()
+            .map(|(identifier, record)| (identifier.clone(), record.clone()))
+            .collect(),
+        villagers_killed: campaign.lifetime_villagers_killed,
+        controls: action_map.clone(),
+    };
+
+    match ron::ser::to_string_pretty(&data, ron::ser::PrettyConfig::default()) {
+        Ok(content) => write(SAVE_NAME, &content),
+        Err(error) => warn!("Could not serialize save data: {}", error),
+    }
+}
+
+/// Read the saved content with the given name, if any.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(name: &str) -> Option<String> {
+    let path = dirs::data_dir()?
+        .join(SAVE_DIRECTORY)
+        .join(format!("{}.ron", name));
+
+    // No save file yet is not an error.
+    std::fs::read_to_string(path).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(name: &str, content: &str) {
+    let Some(directory) = dirs::data_dir().map(|dir| dir.join(SAVE_DIRECTORY)) else {
+        warn!("Could not find a data directory to save to");
+        return;
+    };
+
+    if let Err(error) = std::fs::create_dir_all(&directory)
+        .and_then(|_| std::fs::write(directory.join(format!("{}.ron", name)), content))
+    {
+        warn!("Could not write save data: {}", error);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove(name: &str) {
+    if let Some(path) =
+        dirs::data_dir().map(|dir| dir.join(SAVE_DIRECTORY).join(format!("{}.ron", name)))
+    {
+        // Nothing to remove is fine.
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(name: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("{}{}", SAVE_KEY_PREFIX, name))
+        .ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(name: &str, content: &str) {
+    let Some(storage) = local_storage() else {
+        warn!("Could not access local storage to save to");
+        return;
+    };
+
+    if storage
+        .set_item(&format!("{}{}", SAVE_KEY_PREFIX, name), content)
+        .is_err()
+    {
+        warn!("Could not write save data");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn remove(name: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&format!("{}{}", SAVE_KEY_PREFIX, name));
+    }
+}
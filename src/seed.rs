@@ -0,0 +1,58 @@
+// Random seed
+// Every level starts from a known seed, so villager sprites, wander and flee targets can be reproduced.
+// The seed comes from the `--seed` argument, then the tuning file, otherwise a random one is picked.
+
+use bevy::prelude::*;
+use bevy_rand::prelude::{GlobalEntropy, WyRand};
+use rand_core::{RngCore, SeedableRng};
+
+use crate::{states::PlayingState, tuning::GameTuning};
+
+#[derive(Resource, Reflect, Default, Debug)]
+#[reflect(Resource)]
+pub struct RunSeed {
+    /// Seed given on the command line, used instead of the tuning file.
+    pub fixed: Option<u64>,
+    /// Seed the current level started with.
+    pub current: u64,
+}
+
+impl RunSeed {
+    pub fn fixed(seed: u64) -> Self {
+        Self {
+            fixed: Some(seed),
+            current: seed,
+        }
+    }
+}
+
+/// Systems drawing from the seeded [`GlobalEntropy`] during `Update`.
+/// They run in this order, so the same seed always gives the same draws.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SeededRng {
+    Spawn,
+    Ai,
+}
+
+pub struct SeedPlugin;
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunSeed>()
+            .register_type::<RunSeed>()
+            .configure_sets(Update, (SeededRng::Spawn, SeededRng::Ai).chain())
+            // Once the level and the tuning are loaded, right before entities start using the rng.
+            .add_systems(OnEnter(PlayingState::IntroScene), reseed);
+    }
+}
+
+fn reseed(
+    mut seed: ResMut<RunSeed>,
+    tuning: Res<GameTuning>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+) {
+    seed.current = seed.fixed.or(tuning.seed).unwrap_or_else(|| rng.next_u64());
+    *rng = GlobalEntropy::<WyRand>::from_seed(seed.current.to_le_bytes());
+
+    info!("Level started with seed {}", seed.current);
+}
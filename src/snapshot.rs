@@ -0,0 +1,378 @@
+// Level snapshot
+// Suspend a running level from the pause menu, and resume it later from the main menu.
+// Components are stored through reflection, and matched back to the respawned LDtk entities by their iid.
+
+use std::any::TypeId;
+
+use bevy::{
+    ecs::{entity::EntityHashMap, reflect::ReflectMapEntities, world::CommandQueue},
+    prelude::*,
+    reflect::{
+        serde::{ReflectDeserializer, ReflectSerializer},
+        TypeRegistry,
+    },
+    time::Stopwatch,
+};
+use bevy_ecs_ldtk::{EntityIid, GridCoords, LevelSelection};
+use bevy_rapier2d::prelude::{CollisionGroups, Group};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{
+    ai::{
+        Chase, Chased, CorpseDiscovered, Dead, HasTakedToInvestigator, Idle, Investigate, Patrol,
+        RunAway, Suspicion, TalkToInvestigator, Visit, Wander,
+    },
+    campaign::Campaign,
+    config::PLAYER_ANIMATION_HIDDING,
+    game_mode::Score,
+    ldtk::{
+        animation::new_animation,
+        entities::{
+            add_corpse_sensor, player_respawn_point::PlayerRespawnPointTag, CorpseHidden, EnemyTag,
+            NoiseMakerInvestigateTarget, NoiseMakerTriggerable, PlayerTag,
+        },
+    },
+    player_controller::{Dragging, PlayerIsHidding, SeenHidding},
+    save,
+    states::{GameState, PlayingState},
+};
+
+const SNAPSHOT_NAME: &str = "snapshot";
+
+#[derive(Serialize, Deserialize)]
+struct EntitySnapshot {
+    iid: String,
+    /// The [`Entity`] at the time of the snapshot, to remap references like [`Chase::target`].
+    entity: u64,
+    /// Each component serialized through reflection.
+    components: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LevelSnapshot {
+    level: String,
+    player_lives: usize,
+    time: f32, // In seconds
+    entities: Vec<EntitySnapshot>,
+    /// Respawn points not consumed yet.
+    respawn_points: Vec<String>,
+    /// Noise makers that can't be triggered anymore.
+    used_noise_makers: Vec<String>,
+}
+
+/// Snapshot waiting for the level to be spawned before being applied.
+#[derive(Resource)]
+pub struct PendingSnapshot(LevelSnapshot);
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PlayerIsHidding>()
+            .register_type::<SeenHidding>()
+            .register_type::<GridCoords>()
+            .register_type::<Stopwatch>()
+            .add_systems(
+                // Last, so the defaults inserted by the AI (like [`Idle`]) are already there to be replaced.
+                Last,
+                apply_snapshot.run_if(
+                    in_state(PlayingState::Playing).and_then(resource_exists::<PendingSnapshot>),
+                ),
+            );
+    }
+}
+
+/// Components restored on top of the freshly spawned entities.
+fn snapshot_types() -> [TypeId; 6] {
+    [
+        TypeId::of::<Transform>(),
+        TypeId::of::<PlayerIsHidding>(),
+        TypeId::of::<SeenHidding>(),
+        TypeId::of::<Suspicion>(),
+        TypeId::of::<CorpseDiscovered>(),
+        TypeId::of::<CorpseHidden>(),
+    ]
+}
+
+/// AI states, one of them replaces the default state of the entity.
+fn state_types() -> [TypeId; 11] {
+    [
+        TypeId::of::<Idle>(),
+        TypeId::of::<Wander>(),
+        TypeId::of::<Patrol>(),
+        TypeId::of::<Visit>(),
+        TypeId::of::<Investigate>(),
+        TypeId::of::<Chase>(),
+        TypeId::of::<Chased>(),
+        TypeId::of::<RunAway>(),
+        TypeId::of::<TalkToInvestigator>(),
+        TypeId::of::<HasTakedToInvestigator>(),
+        TypeId::of::<Dead>(),
+    ]
+}
+
+pub fn has_snapshot() -> bool {
+    save::read(SNAPSHOT_NAME).is_some()
+}
+
+/// Store the running level and go back to the main menu.
+pub fn suspend(world: &mut World) {
+    drop_dragged_corpse(world);
+
+    match capture(world).map(|snapshot| ron::ser::to_string(&snapshot)) {
+        Some(Ok(content)) => save::write(SNAPSHOT_NAME, &content),
+        Some(Err(error)) => warn!("Could not serialize level snapshot: {}", error),
+        None => warn!("Could not capture level snapshot"),
+    }
+
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::MainMenu);
+}
+
+/// Load the stored level, the snapshot is applied once it's spawned.
+/// The snapshot is removed, so a level can only be resumed once.
+pub fn resume(world: &mut World) {
+    let Some(content) = save::read(SNAPSHOT_NAME) else {
+        return;
+    };
+    save::remove(SNAPSHOT_NAME);
+
+    let snapshot = match ron::de::from_str::<LevelSnapshot>(&content) {
+        Ok(snapshot) => snapshot,
+        Err(error) => {
+            warn!("Could not parse level snapshot: {}", error);
+            return;
+        }
+    };
+
+    let mut campaign = world.resource_mut::<Campaign>();
+    let Some(index) = campaign
+        .levels
+        .iter()
+        .position(|identifier| *identifier == snapshot.level)
+    else {
+        warn!("Level {} from the snapshot doesn't exist", snapshot.level);
+        return;
+    };
+    campaign.select(index);
+
+    world.insert_resource(LevelSelection::Identifier(snapshot.level.clone()));
+    world.insert_resource(PendingSnapshot(snapshot));
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Reset);
+}
+
+/// Dragging isn't part of the snapshot, so let go of the corpse first, as if the player dropped it.
+/// It is captured where it lies, and can be picked up again once resumed.
+fn drop_dragged_corpse(world: &mut World) {
+    let mut dragging = world.query::<(Entity, &Dragging)>();
+    let Some((player, corpse)) = dragging
+        .iter(world)
+        .next()
+        .map(|(player, dragging)| (player, dragging.0))
+    else {
+        return;
+    };
+
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+    commands.entity(player).remove::<Dragging>();
+    add_corpse_sensor(&mut commands, corpse);
+    queue.apply(world);
+}
+
+fn capture(world: &mut World) -> Option<LevelSnapshot> {
+    let campaign = world.resource::<Campaign>();
+    let level = campaign.levels.get(campaign.current)?.clone();
+
+    let score = world.resource::<Score>();
+    let (player_lives, time) = (score.player_lives(), score.time());
+
+    let mut entities =
+        world.query_filtered::<(Entity, &EntityIid), Or<(With<PlayerTag>, With<EnemyTag>)>>();
+    let mut respawn_points = world.query_filtered::<&EntityIid, With<PlayerRespawnPointTag>>();
+    let mut used_noise_makers = world.query_filtered::<&EntityIid, (
+        With<NoiseMakerInvestigateTarget>,
+        Without<NoiseMakerTriggerable>,
+    )>();
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let entities = entities
+        .iter(world)
+        .map(|(entity, iid)| {
+            let entity_ref = world.entity(entity);
+            let components = snapshot_types()
+                .into_iter()
+                .chain(state_types())
+                .filter_map(|type_id| registry.get(type_id)?.data::<ReflectComponent>())
+                .filter_map(|reflect_component| reflect_component.reflect(entity_ref))
+                .filter_map(|component| {
+                    ron::ser::to_string(&ReflectSerializer::new(component, &registry)).ok()
+                })
+                .collect();
+
+            EntitySnapshot {
+                iid: iid.as_str().to_string(),
+                entity: entity.to_bits(),
+                components,
+            }
+        })
+        .collect();
+
+    Some(LevelSnapshot {
+        level,
+        player_lives,
+        time,
+        entities,
+        respawn_points: respawn_points
+            .iter(world)
+            .map(|iid| iid.as_str().to_string())
+            .collect(),
+        used_noise_makers: used_noise_makers
+            .iter(world)
+            .map(|iid| iid.as_str().to_string())
+            .collect(),
+    })
+}
+
+fn deserialize_component(content: &str, registry: &TypeRegistry) -> Option<Box<dyn Reflect>> {
+    let mut deserializer = ron::de::Deserializer::from_str(content).ok()?;
+    match ReflectDeserializer::new(registry).deserialize(&mut deserializer) {
+        Ok(component) => Some(component),
+        Err(error) => {
+            warn!("Could not parse snapshot component: {}", error);
+            None
+        }
+    }
+}
+
+fn apply_snapshot(world: &mut World) {
+    // Wait for the level to be spawned.
+    let mut player = world.query_filtered::<Entity, With<PlayerTag>>();
+    if player.iter(world).next().is_none() {
+        return;
+    }
+
+    let Some(PendingSnapshot(snapshot)) = world.remove_resource::<PendingSnapshot>() else {
+        return;
+    };
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    let mut ldtk_entities = world.query::<(Entity, &EntityIid)>();
+    let ldtk_entities: Vec<(Entity, String)> = ldtk_entities
+        .iter(world)
+        .map(|(entity, iid)| (entity, iid.as_str().to_string()))
+        .collect();
+    let find = |iid: &str| {
+        ldtk_entities
+            .iter()
+            .find(|(_, entity_iid)| entity_iid == iid)
+            .map(|(entity, _)| *entity)
+    };
+
+    // Old entity to new entity.
+    let mut entity_map = EntityHashMap::default();
+    let mut restored = Vec::new();
+
+    for saved in &snapshot.entities {
+        let Some(entity) = find(&saved.iid) else {
+            continue;
+        };
+        entity_map.insert(Entity::from_bits(saved.entity), entity);
+        restored.push(entity);
+
+        let components: Vec<Box<dyn Reflect>> = saved
+            .components
+            .iter()
+            .filter_map(|content| deserialize_component(content, &registry))
+            .collect();
+        let is_saved = |type_id: TypeId| {
+            components.iter().any(|component| {
+                component
+                    .get_represented_type_info()
+                    .is_some_and(|info| info.type_id() == type_id)
+            })
+        };
+
+        let mut entity_mut = world.entity_mut(entity);
+
+        // Remove the states the entity started with.
+        for type_id in snapshot_types().into_iter().chain(state_types()) {
+            if is_saved(type_id)
+                || type_id == TypeId::of::<Transform>()
+                || type_id == TypeId::of::<Suspicion>()
+            {
+                continue;
+            }
+            if let Some(reflect_component) = registry
+                .get(type_id)
+                .and_then(|registration| registration.data::<ReflectComponent>())
+            {
+                reflect_component.remove(&mut entity_mut);
+            }
+        }
+
+        for component in &components {
+            if let Some(reflect_component) = component
+                .get_represented_type_info()
+                .and_then(|info| registry.get(info.type_id()))
+                .and_then(|registration| registration.data::<ReflectComponent>())
+            {
+                reflect_component.insert(&mut entity_mut, component.as_reflect(), &registry);
+            }
+        }
+
+        // Match what happens when entering a hidding spot.
+        if entity_mut.contains::<PlayerIsHidding>() {
+            entity_mut.insert((
+                CollisionGroups::new(Group::GROUP_2, Group::GROUP_2),
+                new_animation(PLAYER_ANIMATION_HIDDING),
+            ));
+        }
+    }
+
+    // Point references (like who is chased) to the new entities.
+    for type_id in state_types() {
+        if let Some(map_entities) = registry
+            .get(type_id)
+            .and_then(|registration| registration.data::<ReflectMapEntities>())
+        {
+            map_entities.map_entities(world, &mut entity_map, &restored);
+        }
+    }
+
+    // Remove consumed respawn points.
+    let mut respawn_points =
+        world.query_filtered::<(Entity, &EntityIid), With<PlayerRespawnPointTag>>();
+    let consumed: Vec<Entity> = respawn_points
+        .iter(world)
+        .filter(|(_, iid)| {
+            !snapshot
+                .respawn_points
+                .iter()
+                .any(|saved| saved == iid.as_str())
+        })
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in consumed {
+        world.entity_mut(entity).despawn_recursive();
+    }
+
+    // Disable used noise makers.
+    for iid in &snapshot.used_noise_makers {
+        if let Some(entity) = find(iid) {
+            world.entity_mut(entity).remove::<NoiseMakerTriggerable>();
+        }
+    }
+
+    // Kills are counted back when `Dead` is added.
+    world
+        .resource_mut::<Score>()
+        .restore(snapshot.player_lives, snapshot.time);
+}
@@ -7,9 +7,20 @@ pub enum GameState {
     Loading,
     MainMenu,
     Playing,
+    NextLevel,
     Reset,
 }
 
+// Different screens of the main menu.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates, Reflect)]
+#[source(GameState = GameState::MainMenu)]
+pub enum MainMenuState {
+    #[default]
+    Title,
+    LevelSelect,
+    Controls,
+}
+
 // Different states of the playing the game.
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates, Reflect)]
 #[source(GameState = GameState::Playing)]
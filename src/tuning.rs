@@ -0,0 +1,228 @@
+// Gameplay tuning
+// Values designers can tweak without recompiling, loaded from `assets/game.tuning.ron`.
+// Defaults match the constants in `config.rs`, and missing fields in the file fall back to them.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use iyes_progress::prelude::AssetsLoading;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{ai::ScheduledActivity, config::*};
+
+#[derive(Asset, Resource, Reflect, Serialize, Deserialize, Clone, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct GameTuning {
+    pub investigator_view_range: f32,      // In world units
+    pub investigator_view_half_angle: f32, // In degrees
+    pub investigator_hearing_range: f32,   // In world units
+
+    pub villagers_view_range: f32,      // In world units
+    pub villagers_view_half_angle: f32, // In degrees
+
+    pub normal_speed: f32,  // In world units per seconds
+    pub running_speed: f32, // In world units per seconds
+    pub chase_speed: f32,   // In world units per seconds
+
+    pub investigating_radius: u32, // In grid units
+    pub investigating_time: f32,   // In seconds
+
+    pub search_sight_radius: u32, // In grid units
+    pub search_memory_time: f32,  // In seconds
+    pub search_claim_radius: u32, // In grid units
+
+    pub inspect_chance: f32,          // Between 0 and 1
+    pub inspect_time: f32,            // In seconds
+    pub inspect_catch_suspicion: f32, // Between 0 and 1
+
+    pub radio_range: f32,    // In world units
+    pub radio_interval: f32, // In seconds
+    pub flank_distance: u32, // In grid units
+    pub flank_spread: u32,   // In grid units
+
+    pub chase_lead_time: f32,         // In seconds
+    pub chase_prediction_time: f32,   // In seconds
+    pub chase_lead_min_distance: u32, // In grid units
+
+    pub wandering_radius: u32, // In grid units
+
+    pub min_run_away_radius: u32, // In grid units
+    pub max_run_away_radius: u32, // In grid units
+
+    pub idling_time: f32, // In seconds
+
+    pub day_length: f32,     // In seconds, for 24 in-game hours
+    pub day_start_hour: f32, // Between 0 and 24
+    pub activity_time: f32,  // In seconds
+    pub villager_schedule: Vec<ScheduledActivity>,
+
+    pub footstep_loudness: f32, // Fraction of the hearing range, at full speed
+    pub kill_loudness: f32,     // Fraction of the hearing range
+    pub scream_loudness: f32,   // Fraction of the hearing range
+    pub corpse_alarm_loudness: f32, // Fraction of the hearing range
+    pub noisy_hidding_spot_loudness: f32, // Fraction of the hearing range
+    pub max_occluding_walls: u32,
+
+    pub suspicion_build_rate: f32,            // Per seconds, at best
+    pub suspicion_decay_rate: f32,            // Per seconds
+    pub suspicion_investigate_threshold: f32, // Between 0 and 1
+
+    pub ambient_light: f32,    // Between 0 and 1
+    pub dark_view_factor: f32, // View range multiplier in the dark
+
+    pub investigator_lantern_range: f32, // In world units
+    pub villager_lantern_range: f32,     // In world units
+    pub lantern_half_angle: f32,         // In degrees
+
+    pub player_speed: f32,      // In world units per seconds
+    pub drag_speed_factor: f32, // Player speed multiplier when dragging a corpse
+
+    pub interaction_distance: f32, // In world units
+
+    pub pathfinding_budget: u32, // Paths per frame
+
+    pub campaign_carry_over: bool,
+
+    pub seed: Option<u64>, // Random every level when not set
+}
+
+impl Default for GameTuning {
+    fn default() -> Self {
+        Self {
+            investigator_view_range: INVESTIGATOR_VIEW_RANGE,
+            investigator_view_half_angle: INVESTIGATOR_VIEW_HALF_ANGLE,
+            investigator_hearing_range: INVESTIGATOR_HEARING_RANGE,
+            villagers_view_range: VILLAGERS_VIEW_RANGE,
+            villagers_view_half_angle: VILLAGERS_VIEW_HALF_ANGLE,
+            normal_speed: NORMAL_SPEED,
+            running_speed: RUNNING_SPEED,
+            chase_speed: CHASE_SPEED,
+            investigating_radius: INVESTIGATING_RADIUS,
+            investigating_time: INVESTIGATING_TIME as f32,
+            search_sight_radius: SEARCH_SIGHT_RADIUS,
+            search_memory_time: SEARCH_MEMORY_TIME,
+            search_claim_radius: SEARCH_CLAIM_RADIUS,
+            inspect_chance: INSPECT_CHANCE,
+            inspect_time: INSPECT_TIME,
+            inspect_catch_suspicion: INSPECT_CATCH_SUSPICION,
+            radio_range: RADIO_RANGE,
+            radio_interval: RADIO_INTERVAL,
+            flank_distance: FLANK_DISTANCE,
+            flank_spread: FLANK_SPREAD,
+            chase_lead_time: CHASE_LEAD_TIME,
+            chase_prediction_time: CHASE_PREDICTION_TIME,
+            chase_lead_min_distance: CHASE_LEAD_MIN_DISTANCE,
+            wandering_radius: WANDERING_RADIUS,
+            min_run_away_radius: MIN_RUN_AWAY_RADIUS,
+            max_run_away_radius: MAX_RUN_AWAY_RADIUS,
+            idling_time: IDLING_TIME as f32,
+            day_length: DAY_LENGTH,
+            day_start_hour: DAY_START_HOUR,
+            activity_time: ACTIVITY_TIME,
+            villager_schedule: VILLAGER_SCHEDULE
+                .iter()
+                .map(|(from_hour, tag)| ScheduledActivity::new(*from_hour, tag))
+                .collect(),
+            footstep_loudness: FOOTSTEP_LOUDNESS,
+            kill_loudness: KILL_LOUDNESS,
+            scream_loudness: SCREAM_LOUDNESS,
+            corpse_alarm_loudness: CORPSE_ALARM_LOUDNESS,
+            noisy_hidding_spot_loudness: NOISY_HIDDING_SPOT_LOUDNESS,
+            max_occluding_walls: MAX_OCCLUDING_WALLS,
+            suspicion_build_rate: SUSPICION_BUILD_RATE,
+            suspicion_decay_rate: SUSPICION_DECAY_RATE,
+            suspicion_investigate_threshold: SUSPICION_INVESTIGATE_THRESHOLD,
+            ambient_light: AMBIENT_LIGHT,
+            dark_view_factor: DARK_VIEW_FACTOR,
+            investigator_lantern_range: INVESTIGATOR_LANTERN_RANGE,
+            villager_lantern_range: VILLAGER_LANTERN_RANGE,
+            lantern_half_angle: LANTERN_HALF_ANGLE,
+            player_speed: PLAYER_SPEED,
+            drag_speed_factor: DRAG_SPEED_FACTOR,
+            interaction_distance: INTERACTION_DISTANCE,
+            pathfinding_budget: PATHFINDING_BUDGET,
+            campaign_carry_over: CAMPAIGN_CARRY_OVER,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct GameTuningHandle(pub Handle<GameTuning>);
+
+#[derive(Default)]
+struct GameTuningLoader;
+
+#[derive(Debug, Error)]
+enum GameTuningLoaderError {
+    #[error("Could not read tuning file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse tuning file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for GameTuningLoader {
+    type Asset = GameTuning;
+    type Settings = ();
+    type Error = GameTuningLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(ron::de::from_bytes::<GameTuning>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.ron"]
+    }
+}
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<GameTuning>()
+            .init_asset_loader::<GameTuningLoader>()
+            .init_resource::<GameTuning>()
+            .register_type::<GameTuning>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, apply_tuning);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<AssetsLoading>,
+) {
+    let handle: Handle<GameTuning> = asset_server.load("game.tuning.ron");
+    loading.add(&handle);
+
+    commands.insert_resource(GameTuningHandle(handle));
+}
+
+/// Copy the [`GameTuning`] asset into the resource every time it's loaded or modified.
+fn apply_tuning(
+    mut events: EventReader<AssetEvent<GameTuning>>,
+    handle: Res<GameTuningHandle>,
+    assets: Res<Assets<GameTuning>>,
+    mut tuning: ResMut<GameTuning>,
+) {
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0) {
+            if let Some(loaded) = assets.get(&handle.0) {
+                *tuning = loaded.clone();
+            }
+        }
+    }
+}